@@ -1,77 +1,161 @@
+#[cfg(feature = "watch")]
+use std::path::Path;
+
 use crate::{
     error::AocError,
+    provider::get_provider,
+    report::PartRecord,
     task_config::Config,
-    util::{Task, get_day_title_and_answers},
+    util::{Task, get_day_title_and_answers, select_part},
 };
 
-fn assert_print_equal(expected: &str, actual: &str, task: Task) {
+fn assert_print_equal(expected: &str, actual: &str, task: Task) -> bool {
     if expected == actual {
         println!("Task {}: \x1b[0;32mok\x1b[0m", task);
+        true
     } else {
         println!(
             "Task {}: \x1b[0;31mFAILED\x1b[0m
     expected: `{}`,
     actual: `{}`",
             task, expected, actual
-        )
+        );
+        false
     }
 }
 
-fn assert_print_fail(s: &str, task: Task) {
+fn assert_print_fail(s: &str, task: Task) -> bool {
     println!(
         "Task {}: \x1b[0;31mFAILED\x1b[0m
     `{}`",
         task, s
-    )
+    );
+    false
 }
 
-pub async fn assert_answer(out: &str, day: u32, year: i32, config: Config) -> Result<(), AocError> {
-    let info = get_day_title_and_answers(day, year as u32).await?;
-    let (p1, p2) = config.get_answers(out);
+/// Asserts the output against the expected answers in `path`, one line per
+/// task, e.g the `test.expected` file next to the `test` input
+#[cfg(feature = "watch")]
+pub fn assert_expected(
+    out: &str,
+    path: &Path,
+    config: Config,
+    part: Option<Task>,
+) -> Result<bool, AocError> {
+    let expected = std::fs::read_to_string(path)?;
+    let mut expected = expected.lines().map(str::trim).filter(|l| !l.is_empty());
+    let (p1, p2) = select_part(config.get_answers(out), part);
 
-    match (p1, p2, info.part1_answer, info.part2_answer) {
-        (Some(p1), Some(p2), Some(a1), Some(a2)) => {
-            assert_print_equal(&a1, &p1, Task::One);
-            assert_print_equal(&a2, &p2, Task::Two);
-        }
-        (Some(p1), None, Some(a1), Some(a2)) => {
-            assert_print_equal(&a1, &p1, Task::One);
-            assert_print_fail(
-                &format!("Couldn't verify answer against the correct one: {}", a2),
-                Task::Two,
-            );
-        }
-        (None, Some(p2), Some(a1), Some(a2)) => {
-            assert_print_fail(
-                &format!("Couldn't verify answer against the correct one: {}", a1),
-                Task::One,
-            );
-            assert_print_equal(&a2, &p2, Task::Two);
-        }
-        (Some(p1), _, Some(a1), None) if day == 25 => {
-            assert_print_equal(&a1, &p1, Task::One);
+    let mut ok = true;
+    for (task, expected, actual) in [
+        (Task::One, expected.next(), p1),
+        (Task::Two, expected.next(), p2),
+    ] {
+        if part.is_some_and(|part| part != task) {
+            continue;
         }
-        (Some(p1), _, Some(a1), None) => {
-            assert_print_equal(&a1, &p1, Task::One);
-            assert_print_fail("Have you completed it?", Task::Two);
-        }
-        (None, Some(_), Some(a1), None) => {
-            assert_print_fail(
-                &format!("Couldn't verify answer against the correct one: {}", a1),
-                Task::One,
-            );
-            assert_print_fail("Coulnd't find the submitted answer", Task::Two);
-        }
-        (None, None, _, _) => {
-            assert_print_fail("Have you completed it?", Task::One);
-            assert_print_fail("Have you completed it?", Task::Two);
+        ok &= match (expected, actual) {
+            (Some(expected), Some(actual)) => assert_print_equal(expected, &actual, task),
+            (Some(_), None) => assert_print_fail("Couldn't find the answer in the output", task),
+            (None, _) => true,
+        };
+    }
+    Ok(ok)
+}
+
+/// Whether the answers in `parts` are correct, which is what `run --assert`
+/// exits with for both the text and the JSON output. Only `part` counts if
+/// given, and the second part of the last day has no answer to compare
+pub fn passed(parts: &[PartRecord], part: Option<Task>, last_day: bool) -> bool {
+    parts.iter().all(|record| match part {
+        Some(task) if task.number() != record.part => true,
+        None if last_day && record.part == 2 && record.expected.is_none() => true,
+        _ => record.correct == Some(true),
+    })
+}
+
+/// Prints how the answers in `parts` compare to the submitted ones
+pub fn print_comparison(parts: &[PartRecord], part: Option<Task>, last_day: bool) {
+    for record in parts {
+        let task = if record.part == 1 {
+            Task::One
+        } else {
+            Task::Two
+        };
+        if part.is_some_and(|part| part != task)
+            || (part.is_none() && last_day && task == Task::Two && record.expected.is_none())
+        {
+            continue;
         }
-        // Assumes that it is impossible to get answer for part 2 if we don't get answer for part 1
-        (_, _, None, _) => {
-            assert_print_fail("Coulnd't find the submitted answer", Task::One);
-            assert_print_fail("Coulnd't find the submitted answer", Task::Two);
+        match (&record.answer, &record.expected) {
+            (Some(actual), Some(expected)) => {
+                assert_print_equal(expected, actual, task);
+            }
+            (None, Some(expected)) => {
+                assert_print_fail(
+                    &format!(
+                        "Couldn't find the answer in the output, the correct one is {}",
+                        expected
+                    ),
+                    task,
+                );
+            }
+            (_, None) => {
+                assert_print_fail(
+                    "Couldn't find the submitted answer, have you completed it?",
+                    task,
+                );
+            }
         }
     }
+}
+
+/// Prints how the answers in the output compare to the submitted ones, and
+/// returns whether they are correct (see [`passed`])
+pub async fn assert_answer(
+    out: &str,
+    day: u32,
+    year: i32,
+    config: Config,
+    part: Option<Task>,
+) -> Result<bool, AocError> {
+    let info = get_day_title_and_answers(day, year as u32).await?;
+    let (p1, p2) = select_part(config.get_answers(out), part);
+    let parts = [
+        PartRecord::new(1, p1, info.part1_answer, None),
+        PartRecord::new(2, p2, info.part2_answer, None),
+    ];
+    let last_day = day == get_provider()?.days(year);
 
-    Ok(())
+    print_comparison(&parts, part, last_day);
+    Ok(passed(&parts, part, last_day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::passed;
+    use crate::{report::PartRecord, util::Task};
+
+    #[test]
+    fn test_passed() {
+        let parts = |a2: Option<&str>, e2: Option<&str>| {
+            [
+                PartRecord::new(1, Some("1".to_owned()), Some("1".to_owned()), None),
+                PartRecord::new(2, a2.map(str::to_owned), e2.map(str::to_owned), None),
+            ]
+        };
+
+        assert!(passed(&parts(Some("2"), Some("2")), None, false));
+        assert!(!passed(&parts(Some("3"), Some("2")), None, false));
+        assert!(!passed(&parts(None, Some("2")), None, false));
+        // Part two is not submitted yet
+        assert!(!passed(&parts(Some("2"), None), None, false));
+        assert!(passed(&parts(Some("2"), None), None, true));
+        assert!(passed(&parts(Some("3"), Some("2")), Some(Task::One), false));
+        assert!(!passed(
+            &parts(Some("3"), Some("2")),
+            Some(Task::Two),
+            false
+        ));
+    }
 }
@@ -5,6 +5,8 @@ use tokio::fs;
 
 use crate::{
     error::AocError,
+    language::{Common, Mode, RunningArgs},
+    provider::input_path,
     util::{file::*, get_day},
 };
 
@@ -30,7 +32,7 @@ criterion_main!(benches);
 
     let remove_errors = "#![allow(dead_code)]";
 
-    let input = path.join("input");
+    let input = input_path(path, None);
     let tests = tests.replace("XXX", &input.display().to_string());
     let tests = tests.replace("read_input(\"", "read_input(r\"");
 
@@ -74,18 +76,32 @@ fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(), AocE
 }
 
 pub async fn bench(matches: &ArgMatches) -> Result<(), AocError> {
-    let day = get_day(matches)?;
     let root_folder = get_root_path()?;
-    let day_path = day_path(root_folder, day).await?;
+    let day = get_day(matches, get_year_from_path(&root_folder)?)?;
+    let day_path = day_path(&root_folder, day).await?;
 
     if !day_path.join(".bench").exists() {
         create_bench_foler(&day_path).await?;
     }
     create_file(&day_path).await?;
 
+    let args = RunningArgs {
+        mode: Mode::Bench,
+        common: Common {
+            day: day as i32,
+            year: get_year_from_path(&root_folder)?,
+            input_file: input_path(&day_path, None),
+            day_folder: day_path.clone(),
+            root_folder,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
     tokio::process::Command::new("cargo")
         .arg("bench")
         .current_dir(day_path.join(".bench"))
+        .envs(args.env())
         .spawn()?
         .wait()
         .await?;
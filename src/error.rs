@@ -11,26 +11,47 @@ pub enum AocError {
     #[error("reqwest error: {0}")]
     ReqwestError(#[from] reqwest::Error),
 
+    #[error("http cassette error: {0}")]
+    CassetteError(String),
+
     #[error("download error: {0}")]
     DownloadError(String),
 
     #[error("error from adventofcode.com: {0}")]
     ApiError(String),
 
+    #[error("error parsing puzzle page: {0}")]
+    PageError(#[from] crate::util::page::PageError),
+
     #[cfg(feature = "submit")]
     #[error("Error on sanitizing answer")]
     SanitizeHtml,
 
+    #[cfg(feature = "submit")]
     #[error("Error on getting answer from task")]
     ParseStdout,
 
-    #[error("Day must be between 1 and 25")]
-    InvalidRunDay,
+    #[error("Day must be between 1 and {0}")]
+    InvalidRunDay(u32),
 
     #[cfg(feature = "submit")]
     #[error("Can only submit task 1 or 2")]
     InvalidSubmitTask,
 
+    #[cfg(feature = "submit")]
+    #[error("Can only submit the part given with --part")]
+    SubmitOtherPart,
+
+    #[cfg(feature = "submit")]
+    #[error("Can only submit for a single day")]
+    SubmitMultipleDays,
+
+    #[error("error serializing results: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("solution exited with {0}")]
+    SolutionFailed(std::process::ExitStatus),
+
     #[error("Year must be between 2015 ..= current year")]
     InvalidYear,
     #[error("Its not yet december for this year's puzzles!")]
@@ -52,14 +73,60 @@ pub enum AocError {
     #[error("argument error {0}")]
     ArgError(String),
 
+    #[cfg(feature = "watch")]
+    #[error("watch error: {0}")]
+    WatchError(#[from] notify::Error),
+
+    #[error("Solution timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Solution exceeded the memory limit of {}", crate::supervise::format_memory(*.0))]
+    OutOfMemory(u64),
+
+    #[error("Invalid limit: {0}")]
+    LimitError(String),
+
     #[error("Setup for year already exists")]
     SetupExists,
 
+    #[error("Unsupported provider {0}")]
+    UnsupportedProvider(String),
+
     #[error("Unsupported language with extension {0}")]
     UnsupportedLanguage(String),
 
-    #[error("Error when replacing template string: {0}")]
-    TemplateError(String),
+    #[error("No {0} command for .{1} files in .languages.toml")]
+    MissingCommand(crate::language::Tool, String),
+
+    #[error("Could not find a main file in {0}")]
+    MissingMain(std::path::PathBuf),
+
+    #[error("Could not find a main.{1} file in {0}")]
+    MissingLang(std::path::PathBuf, String),
+
+    #[error("{0} has a main file in {langs}, pick one with --lang", langs = .1.join(", "))]
+    AmbiguousMain(std::path::PathBuf, Vec<String>),
+
+    #[error("Invalid toolchain `{0}` in .languages.toml: {1}")]
+    ToolchainError(String, String),
+
+    #[error("Invalid config {location}: {message}")]
+    ConfigError { location: String, message: String },
+
+    #[error("Invalid template in `{command}` at column {column}: {message}")]
+    TemplateError {
+        command: String,
+        column: usize,
+        message: String,
+    },
+
+    #[cfg(feature = "browser")]
+    #[error("Could not import cookie from browser: {0}")]
+    BrowserError(String),
+
+    #[cfg(feature = "browser")]
+    #[error("sqlite error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
 
     #[error("Error when trying to deserialize string {0}")]
     TomlDeserializeError(#[from] toml::de::Error),
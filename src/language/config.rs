@@ -1,30 +1,133 @@
 use std::{collections::HashMap, marker::PhantomData, path::Path};
 
 use duct::{Expression, cmd};
-use regex::{Captures, Regex};
 use serde::Deserialize;
 
 use crate::{
     error::AocError,
-    language::{Runner, RunningArgs, r#trait::Ext},
+    language::{Runner, RunningArgs, Tool, r#trait::Ext},
+    util::file::read_toml,
 };
 
-#[derive(Debug, Deserialize)]
+/// The toolchains of all layers of `.languages.toml` merged, with `extends`
+/// resolved
+#[derive(Debug, Default)]
 pub struct Config {
     pub toolchain: HashMap<String, Toolchain<Raw>>,
+    /// The name of the toolchain that runs files with each extension
+    pub extensions: HashMap<String, String>,
+    /// Default for `run --release`
+    pub release: bool,
 }
 
-impl Config {
+/// A single `.languages.toml`
+#[derive(Debug, Default, Deserialize)]
+pub struct Layer {
+    #[serde(default)]
+    toolchain: HashMap<String, PartialToolchain>,
+    /// Picks the toolchain for an extension several toolchains have
+    #[serde(default)]
+    extensions: HashMap<String, String>,
+    /// Default for `run --release` in the days the layer applies to
+    release: Option<bool>,
+}
+
+impl Layer {
     pub fn from_file(p: &Path) -> Result<Self, AocError> {
-        let s = std::fs::read_to_string(p)?;
-        let res = toml::from_str(&s)?;
+        read_toml(p)
+    }
+}
+
+impl Config {
+    /// Merges the layers, lowest precedence first. A toolchain in a later
+    /// layer overrides the fields it sets of the same toolchain in an earlier
+    /// one, and only then is `extends` resolved. Toolchains that can not be
+    /// resolved are skipped, and returned as errors.
+    ///
+    /// When several toolchains have the same extension, the one picked in
+    /// `extensions` runs it, or else the first by name that has no `markers`
+    pub fn from_layers(layers: impl IntoIterator<Item = Layer>) -> (Self, Vec<AocError>) {
+        let mut merged: HashMap<String, PartialToolchain> = HashMap::new();
+        let mut picked = HashMap::new();
+        let mut release = false;
+        for layer in layers {
+            picked.extend(layer.extensions);
+            release = layer.release.unwrap_or(release);
+            for (name, toolchain) in layer.toolchain {
+                let toolchain = match merged.remove(&name) {
+                    Some(base) => toolchain.over(&base),
+                    None => toolchain,
+                };
+                merged.insert(name, toolchain);
+            }
+        }
+
+        let mut names = merged.keys().collect::<Vec<_>>();
+        names.sort();
+        let mut toolchain = HashMap::new();
+        let mut errors = Vec::new();
+        for name in names {
+            match resolve(name, &merged, &mut Vec::new()).and_then(|t| t.build(name)) {
+                Ok(t) => {
+                    toolchain.insert(name.clone(), t);
+                }
+                Err(err) => errors.push(err),
+            }
+        }
 
-        Ok(res)
+        let mut picks = picked.iter().collect::<Vec<_>>();
+        picks.sort();
+        for (ext, name) in picks {
+            let message = match toolchain.get(name) {
+                Some(t) if &t.ext == ext => continue,
+                Some(t) => format!("picked for .{} files, but it runs .{} files", ext, t.ext),
+                // Already reported as invalid
+                None if merged.contains_key(name) => continue,
+                None => format!("picked for .{} files, but it is not defined", ext),
+            };
+            errors.push(AocError::ToolchainError(name.clone(), message));
+        }
+
+        let mut names = toolchain.keys().collect::<Vec<_>>();
+        names.sort_by_key(|name| (!toolchain[*name].markers.is_empty(), *name));
+        let mut extensions = HashMap::new();
+        for name in names {
+            let ext = &toolchain[name].ext;
+            let is_picked = picked.get(ext) == Some(name);
+            if is_picked || !extensions.contains_key(ext) {
+                extensions.insert(ext.clone(), name.clone());
+            }
+        }
+
+        let config = Config {
+            toolchain,
+            extensions,
+            release,
+        };
+        (config, errors)
     }
+
+    /// The toolchains picked for their extension
+    fn active(&self) -> impl Iterator<Item = &Toolchain<Raw>> {
+        self.extensions.values().map(|name| &self.toolchain[name])
+    }
+
+    /// The toolchains with `markers`, by name
+    fn projects(&self) -> impl Iterator<Item = &Toolchain<Raw>> {
+        let mut names = self
+            .toolchain
+            .iter()
+            .filter(|(_, toolchain)| !toolchain.markers.is_empty())
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        names.sort();
+        names.into_iter().map(|name| &self.toolchain[name])
+    }
+
     pub fn runners(&self) -> Vec<impl Runner + 'static> {
         let mut vec = Vec::new();
 
-        for value in self.toolchain.values() {
+        for value in self.active() {
             vec.push(value.runner());
         }
 
@@ -33,7 +136,7 @@ impl Config {
     pub fn compilers(&self) -> Vec<impl super::r#trait::Compile + 'static> {
         let mut vec = Vec::new();
 
-        for value in self.toolchain.values() {
+        for value in self.active() {
             // vec.push(value.compiler());
             if let Some(c) = value.compiler() {
                 vec.push(c);
@@ -42,25 +145,198 @@ impl Config {
 
         vec
     }
+
+    /// Like [`Config::runners`], for the toolchains found by their markers
+    pub fn project_runners(&self) -> Vec<impl Runner + 'static> {
+        self.projects().map(Toolchain::runner).collect()
+    }
+    pub fn project_compilers(&self) -> Vec<impl super::r#trait::Compile + 'static> {
+        self.projects().filter_map(Toolchain::compiler).collect()
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// A toolchain as written in a `.languages.toml`, where every field is
+/// optional as it may come from another layer or the toolchain it extends
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct PartialToolchain {
+    extends: Option<String>,
+    run: Option<String>,
+    run_release: Option<String>,
+    ext: Option<String>,
+    entry: Option<String>,
+    markers: Option<Vec<String>>,
+    needs_entry: Option<bool>,
+    dir: Option<String>,
+    input: Option<InputMode>,
+    test: Option<String>,
+    lint: Option<Check>,
+    fmt: Option<Check>,
+    compile: Option<Compile>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+}
+
+impl PartialToolchain {
+    /// The fields set in `self` take precedence over the ones in `base`,
+    /// and the `env` tables are merged
+    fn over(self, base: &PartialToolchain) -> PartialToolchain {
+        let mut env = base.env.clone();
+        env.extend(self.env);
+
+        PartialToolchain {
+            extends: self.extends.or_else(|| base.extends.clone()),
+            run: self.run.or_else(|| base.run.clone()),
+            run_release: self.run_release.or_else(|| base.run_release.clone()),
+            ext: self.ext.or_else(|| base.ext.clone()),
+            entry: self.entry.or_else(|| base.entry.clone()),
+            markers: self.markers.or_else(|| base.markers.clone()),
+            needs_entry: self.needs_entry.or(base.needs_entry),
+            dir: self.dir.or_else(|| base.dir.clone()),
+            input: self.input.or(base.input),
+            test: self.test.or_else(|| base.test.clone()),
+            lint: self.lint.or_else(|| base.lint.clone()),
+            fmt: self.fmt.or_else(|| base.fmt.clone()),
+            compile: self.compile.or_else(|| base.compile.clone()),
+            env,
+        }
+    }
+
+    fn build(self, name: &str) -> Result<Toolchain<Raw>, AocError> {
+        let missing =
+            |field| AocError::ToolchainError(name.to_owned(), format!("missing `{}`", field));
+
+        Ok(Toolchain {
+            run: self.run.ok_or_else(|| missing("run"))?,
+            run_release: self.run_release,
+            ext: self.ext.ok_or_else(|| missing("ext"))?,
+            entry: self.entry.unwrap_or_else(|| "main".to_owned()),
+            markers: self.markers.unwrap_or_default(),
+            needs_entry: self.needs_entry.unwrap_or_default(),
+            dir: self.dir,
+            input: self.input.unwrap_or_default(),
+            test: self.test,
+            lint: self.lint,
+            fmt: self.fmt,
+            compile: self.compile,
+            env: self.env,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// The toolchain `name` with the toolchains it extends applied
+fn resolve(
+    name: &str,
+    toolchains: &HashMap<String, PartialToolchain>,
+    seen: &mut Vec<String>,
+) -> Result<PartialToolchain, AocError> {
+    seen.push(name.to_owned());
+    let error = |message| AocError::ToolchainError(seen[0].clone(), message);
+
+    let Some(toolchain) = toolchains.get(name) else {
+        return Err(error(format!("extends unknown toolchain `{}`", name)));
+    };
+    let Some(parent) = &toolchain.extends else {
+        return Ok(toolchain.clone());
+    };
+    if seen.contains(parent) {
+        return Err(error(format!("`{}` extends itself", parent)));
+    }
+
+    let base = resolve(parent, toolchains, seen)?;
+    Ok(toolchain.clone().over(&base))
+}
+
+#[derive(Debug, Clone)]
 pub struct Toolchain<State> {
     run: String,
+    /// Used instead of `run` for `run --release`. Falls back to `compile`
+    run_release: Option<String>,
     ext: String,
+    /// The name of the file to run, without the extension
+    entry: String,
+    /// Files in the day folder that make this toolchain run the day, like
+    /// `Cargo.toml`, even when it has no entry file
+    markers: Vec<String>,
+    /// Only run the day when it has an entry file, for toolchains whose
+    /// commands need a `{file}` even with a marker
+    needs_entry: bool,
     dir: Option<String>,
+    input: InputMode,
+    test: Option<String>,
+    lint: Option<Check>,
+    fmt: Option<Check>,
     compile: Option<Compile>,
-    #[serde(skip)]
+    /// Set for every command of the toolchain
+    env: HashMap<String, String>,
     _phantom: PhantomData<State>,
 }
 
 impl Toolchain<Raw> {
+    pub fn ext(&self) -> &str {
+        &self.ext
+    }
+
+    pub fn markers(&self) -> &[String] {
+        &self.markers
+    }
+
+    /// The commands of cargo-aoc the toolchain supports, `release` meaning
+    /// `run --release` with a command of its own
+    pub fn commands(&self) -> Vec<&'static str> {
+        [
+            ("run", true),
+            (
+                "release",
+                self.run_release.is_some() || self.compile.is_some(),
+            ),
+            ("test", self.test.is_some()),
+            ("lint", self.lint.is_some()),
+            ("fmt", self.fmt.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(command, supported)| supported.then_some(command))
+        .collect()
+    }
+
+    /// The programs the commands of the toolchain start, e.g `cc` and `mkdir`
+    /// for `mkdir -p target && cc {file}`, except the ones it builds itself
+    pub fn programs(&self) -> Vec<String> {
+        let compile = self
+            .compile
+            .iter()
+            .flat_map(|compile| compile.build.iter().chain([&compile.execute]));
+
+        let mut programs = Vec::new();
+        for command in std::iter::once(&self.run).chain(compile) {
+            let segments = command.split("&&").flat_map(|s| s.split(['|', ';']));
+            for segment in segments {
+                let program = segment.split_whitespace().find(|word| !word.contains('='));
+                if let Some(program) = program.filter(|p| !p.contains(['{', '/']))
+                    && !programs.iter().any(|known| known == program)
+                {
+                    programs.push(program.to_owned());
+                }
+            }
+        }
+        programs
+    }
+
     fn runner(&self) -> Toolchain<RunState> {
         Toolchain {
             run: self.run.clone(),
+            run_release: self.run_release.clone(),
             ext: self.ext.clone(),
+            entry: self.entry.clone(),
+            markers: self.markers.clone(),
+            needs_entry: self.needs_entry,
             dir: self.dir.clone(),
+            input: self.input,
+            test: self.test.clone(),
+            lint: self.lint.clone(),
+            fmt: self.fmt.clone(),
             compile: self.compile.clone(),
+            env: self.env.clone(),
             _phantom: PhantomData,
         }
     }
@@ -68,14 +344,46 @@ impl Toolchain<Raw> {
     fn compiler(&self) -> Option<Toolchain<CompileState>> {
         self.compile.as_ref().map(|_| Toolchain {
             run: self.run.clone(),
+            run_release: self.run_release.clone(),
             ext: self.ext.clone(),
+            entry: self.entry.clone(),
+            markers: self.markers.clone(),
+            needs_entry: self.needs_entry,
             dir: self.dir.clone(),
+            input: self.input,
+            test: self.test.clone(),
+            lint: self.lint.clone(),
+            fmt: self.fmt.clone(),
             compile: self.compile.clone(),
+            env: self.env.clone(),
             _phantom: PhantomData,
         })
     }
 }
 
+/// How the input is given to the solution by `run` and `compile.execute`
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMode {
+    /// The path as the first argument
+    #[default]
+    Arg,
+    /// The content on stdin
+    Stdin,
+    /// Only the path in `AOC_INPUT`
+    Env,
+    /// Not at all, for solutions that find the input themselves
+    None,
+}
+
+/// A command that only checks the day, and optionally one that fixes what
+/// it can
+#[derive(Debug, Deserialize, Clone)]
+pub struct Check {
+    check: String,
+    fix: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Compile {
     build: Option<String>,
@@ -93,36 +401,79 @@ impl Ext for Toolchain<RunState> {
     fn extension(&self) -> &str {
         &self.ext
     }
+
+    fn entry(&self) -> &str {
+        &self.entry
+    }
+
+    fn markers(&self) -> &[String] {
+        &self.markers
+    }
+
+    fn needs_entry(&self) -> bool {
+        self.needs_entry
+    }
 }
 
 impl Ext for Toolchain<CompileState> {
     fn extension(&self) -> &str {
         &self.ext
     }
+
+    fn entry(&self) -> &str {
+        &self.entry
+    }
+
+    fn markers(&self) -> &[String] {
+        &self.markers
+    }
+
+    fn needs_entry(&self) -> bool {
+        self.needs_entry
+    }
 }
 
 impl Runner for Toolchain<RunState> {
     fn execute(&self, args: super::RunningArgs) -> Result<duct::Expression, AocError> {
+        if args.release {
+            if let Some(run_release) = &self.run_release {
+                return run_command(run_release, self, &args, true);
+            }
+            if let Some(compile) = &self.compile {
+                return compile_command(compile, self, &args);
+            }
+        }
         run_command(&self.run, self, &args, true)
     }
-}
 
-fn shell_quote(s: &str) -> String {
-    format!("'{}'", s)
+    fn tool(&self, tool: Tool, args: super::RunningArgs) -> Option<Result<Expression, AocError>> {
+        let command = match tool {
+            Tool::Test => self.test.as_ref(),
+            Tool::Lint => self.lint.as_ref().map(|lint| &lint.check),
+            Tool::LintFix => self.lint.as_ref().and_then(|lint| lint.fix.as_ref()),
+            Tool::Fmt => self.fmt.as_ref().map(|fmt| &fmt.check),
+            Tool::FmtFix => self.fmt.as_ref().and_then(|fmt| fmt.fix.as_ref()),
+        }?;
+        Some(run_command(command, self, &args, false))
+    }
 }
 
 fn transform_command(
     command: &str,
     running_args: &RunningArgs,
     include_args: bool,
+    input: InputMode,
 ) -> (String, Vec<String>) {
     let mut command = command.to_owned();
 
     if include_args {
-        let input = running_args.common.input_file.display().to_string();
-        let rest = running_args.arguments.join(" ");
-
-        command.push_str(&format!(" {} {}", shell_quote(&input), rest));
+        if input == InputMode::Arg {
+            let input = running_args.common.input_file.display().to_string();
+            command.push_str(&format!(" {}", shell_quote(&input)));
+        }
+        for arg in &running_args.arguments {
+            command.push_str(&format!(" {}", shell_quote(arg)));
+        }
     }
     if cfg!(windows) {
         ("cmd".to_owned(), vec!["/c".to_string(), command])
@@ -137,102 +488,558 @@ fn run_command<T>(
     args: &RunningArgs,
     include_args: bool,
 ) -> Result<Expression, AocError> {
-    let run = expand_templates(command, args)?;
+    let run = expand_templates(command, args, true)?;
 
-    let (program, vec) = transform_command(&run, args, include_args);
+    let (program, vec) = transform_command(&run, args, include_args, t.input);
 
     let mut cmd = cmd(program, vec);
     if let Some(dir) = &t.dir {
-        let dir = expand_templates(dir, args)?;
+        let dir = expand_templates(dir, args, false)?;
         cmd = cmd.dir(dir);
     } else {
         cmd = cmd.dir(&args.common.day_folder);
     }
+    for (key, value) in args.env() {
+        cmd = cmd.env(key, value);
+    }
+    for (key, value) in &t.env {
+        cmd = cmd.env(key, expand_templates(value, args, false)?);
+    }
+    if include_args && t.input == InputMode::Stdin {
+        cmd = cmd.stdin_path(&args.common.input_file);
+    }
 
     Ok(cmd)
 }
 
-impl super::r#trait::Compile for Toolchain<CompileState> {
-    fn compile(&self, args: super::RunningArgs) -> Result<duct::Expression, AocError> {
-        let compile = self.compile.as_ref().unwrap();
-        if let Some(build) = &compile.build {
-            let expr = run_command(build, self, &args, false)?;
-            let out = expr.stderr_to_stdout().stdout_capture().unchecked().run()?;
-            if !out.status.success() {
-                let err = std::str::from_utf8(&out.stdout).unwrap();
-                let err_line = err.lines().find(|line| line.starts_with("error: "));
-                let io = std::io::Error::other(err_line.unwrap_or(err));
-                return Err(AocError::StdIoErr(io));
-            }
+fn compile_command<T>(
+    compile: &Compile,
+    t: &Toolchain<T>,
+    args: &RunningArgs,
+) -> Result<Expression, AocError> {
+    if let Some(build) = &compile.build {
+        let expr = run_command(build, t, args, false)?;
+        let out = expr.stderr_to_stdout().stdout_capture().unchecked().run()?;
+        if !out.status.success() {
+            let err = std::str::from_utf8(&out.stdout).unwrap();
+            let err_line = err.lines().find(|line| line.starts_with("error: "));
+            let io = std::io::Error::other(err_line.unwrap_or(err));
+            return Err(AocError::StdIoErr(io));
         }
-
-        run_command(&compile.execute, self, &args, true)
     }
+
+    run_command(&compile.execute, t, args, true)
 }
 
-fn replace_all<E>(
-    re: &Regex,
-    haystack: &str,
-    replacement: impl Fn(&Captures) -> Result<String, E>,
-) -> Result<String, E> {
-    let mut new = String::with_capacity(haystack.len());
-    let mut last_match = 0;
-    for caps in re.captures_iter(haystack) {
-        let m = caps.get(0).unwrap();
-        new.push_str(&haystack[last_match..m.start()]);
-        new.push_str(&replacement(&caps)?);
-        last_match = m.end();
+impl super::r#trait::Compile for Toolchain<CompileState> {
+    fn compile(&self, args: super::RunningArgs) -> Result<duct::Expression, AocError> {
+        compile_command(self.compile.as_ref().unwrap(), self, &args)
     }
-    new.push_str(&haystack[last_match..]);
-    Ok(new)
 }
 
-pub fn expand_templates(input: &str, args: &RunningArgs) -> Result<String, AocError> {
-    let re = Regex::new(r"\{([^}]+)\}").unwrap();
-    let forwarded = args.arguments.join(" ");
+/// Expands the placeholders of a toolchain command, e.g `{file}`,
+/// `{rel:input}` or `{daynum:02}`. `{{` and `}}` are literal braces. With
+/// `quote`, the values are quoted for the shell
+pub fn expand_templates(input: &str, args: &RunningArgs, quote: bool) -> Result<String, AocError> {
+    let error = |offset: usize, message: String| AocError::TemplateError {
+        command: input.to_owned(),
+        column: input[..offset].chars().count() + 1,
+        message,
+    };
 
-    let r#fn = |caps: &regex::Captures| {
-        let raw = &caps[1];
+    let mut new = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find(['{', '}']) {
+        new.push_str(&rest[..pos]);
+        let offset = input.len() - rest.len() + pos;
+        let tail = &rest[pos..];
 
-        let mut parts = raw.splitn(2, ':');
-        let first = parts.next().unwrap();
-        let second = parts.next();
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            new.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            return Err(error(
+                offset,
+                "unmatched `}`, use `}}` for a literal brace".to_owned(),
+            ));
+        }
 
-        let (prefix, key) = match second {
-            Some(key) => (first, key),
-            None => ("", first),
+        let Some(end) = tail.find('}').filter(|end| !tail[1..*end].contains('{')) else {
+            return Err(error(
+                offset,
+                "unclosed `{`, use `{{` for a literal brace".to_owned(),
+            ));
         };
+        let value = placeholder(&tail[1..end], args, quote).map_err(|msg| error(offset, msg))?;
+        new.push_str(&value);
+        rest = &tail[end + 1..];
+    }
+    new.push_str(rest);
 
-        let s = match key {
-            "day" => &args.common.day_folder,
-            "file" => &args.common.file,
-            "args" => return Ok(forwarded.clone()),
-            _ => return Err(AocError::TemplateError(format!("template: {}", key))),
-        };
+    Ok(new)
+}
+
+fn placeholder(raw: &str, args: &RunningArgs, quote: bool) -> Result<String, String> {
+    let quoted = |s: String| if quote { shell_quote(&s) } else { s };
+
+    let (prefix, key) = match raw.split_once(':') {
+        Some(("env", var)) => {
+            return std::env::var(var)
+                .map(quoted)
+                .map_err(|_| format!("environment variable `{}` is not set", var));
+        }
+        Some((key @ ("year" | "daynum"), spec)) => {
+            let num = if key == "year" {
+                args.common.year
+            } else {
+                args.common.day
+            };
+            let width = spec
+                .strip_prefix('0')
+                .and_then(|width| width.parse::<usize>().ok())
+                .ok_or_else(|| {
+                    format!("invalid format `{}`, expected e.g `{{{}:02}}`", spec, key)
+                })?;
+            return Ok(format!("{:0width$}", num, width = width));
+        }
+        Some((prefix, key)) => (prefix, key),
+        None => ("", raw),
+    };
 
-        match prefix {
-            "" => Ok(abs(s)),
-            "rel" => Ok(rel(s, args)),
-            "name" => Ok(name(s)),
-            _ => Err(AocError::TemplateError(format!("prefix: {}", prefix))),
+    let path = match key {
+        "day" => &args.common.day_folder,
+        "file" => &args.common.file,
+        "input" => &args.common.input_file,
+        "root" => &args.common.root_folder,
+        _ if !prefix.is_empty() => return Err(format!("unknown path `{}`", key)),
+        "year" => return Ok(args.common.year.to_string()),
+        "daynum" => return Ok(args.common.day.to_string()),
+        "bin" => return cargo_bin(&args.common.day_folder).map(quoted),
+        "part" => {
+            return Ok(args
+                .part
+                .map(|p| p.number().to_string())
+                .unwrap_or_default());
         }
+        "args" if quote => {
+            let args = args.arguments.iter().map(|arg| shell_quote(arg));
+            return Ok(args.collect::<Vec<_>>().join(" "));
+        }
+        "args" => return Ok(args.arguments.join(" ")),
+        _ => return Err(format!("unknown placeholder `{}`", key)),
+    };
+
+    match prefix {
+        "" => Ok(quoted(abs(path))),
+        "rel" => Ok(quoted(rel(path, args))),
+        "name" => Ok(quoted(name(path))),
+        "stem" => Ok(quoted(stem(path))),
+        _ => Err(format!("unknown prefix `{}`", prefix)),
+    }
+}
+
+/// The name of the binary `cargo build` makes for the `Cargo.toml` in
+/// `day`: its `default-run`, its only `[[bin]]` or else the package name
+fn cargo_bin(day: &Path) -> Result<String, String> {
+    let path = day.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+    let manifest = toml::from_str::<toml::Table>(&manifest)
+        .map_err(|err| format!("could not parse {}: {}", path.display(), err.message()))?;
+
+    let package = manifest.get("package").and_then(|p| p.as_table());
+    let field = |table: Option<&toml::Table>, key| {
+        table
+            .and_then(|table| table.get(key))
+            .and_then(|value| value.as_str())
+            .map(str::to_owned)
     };
+    if let Some(name) = field(package, "default-run") {
+        return Ok(name);
+    }
+    match manifest.get("bin").and_then(|bins| bins.as_array()) {
+        Some(bins) if bins.len() == 1 => field(bins[0].as_table(), "name")
+            .ok_or_else(|| format!("the [[bin]] in {} has no name", path.display())),
+        Some(bins) if bins.len() > 1 => Err(format!(
+            "{} has several binaries, pick one with `default-run`",
+            path.display()
+        )),
+        _ => {
+            field(package, "name").ok_or_else(|| format!("{} has no package name", path.display()))
+        }
+    }
+}
 
-    replace_all(&re, input, r#fn)
+/// Quotes `s` as a single word for `sh`, or leaves it as is when that is
+/// safe
+pub fn shell_quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-+=./:,@%".contains(c);
+    if !s.is_empty() && s.chars().all(safe) {
+        return s.to_owned();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
 }
 
 fn abs(p: &Path) -> String {
     p.display().to_string()
 }
 fn name(p: &Path) -> String {
-    p.file_name().unwrap().to_str().unwrap().to_string()
+    p.file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
+fn stem(p: &Path) -> String {
+    p.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+/// Relative to the root of the year, or absolute when it is outside of it
 fn rel(p: &Path, args: &RunningArgs) -> String {
     p.strip_prefix(&args.common.root_folder)
-        .unwrap()
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string()
+        .map(abs)
+        .unwrap_or_else(|_| abs(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> RunningArgs {
+        RunningArgs {
+            arguments: vec!["--flag".to_owned()],
+            common: crate::language::Common {
+                input_file: "/aoc/day_01/input".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_input_modes() {
+        let command = |input| transform_command("run", &args(), true, input).1[1].clone();
+
+        assert_eq!(command(InputMode::Arg), "run /aoc/day_01/input --flag");
+        assert_eq!(command(InputMode::Stdin), "run --flag");
+        assert_eq!(command(InputMode::Env), "run --flag");
+        assert_eq!(command(InputMode::None), "run --flag");
+        assert_eq!(
+            transform_command("build", &args(), false, InputMode::Arg).1[1],
+            "build"
+        );
+    }
+
+    fn config(s: &str) -> Config {
+        Config::from_layers([toml::from_str::<Layer>(s).unwrap()]).0
+    }
+
+    fn day_args() -> RunningArgs {
+        RunningArgs {
+            arguments: vec!["it's".to_owned(), "x".to_owned()],
+            common: crate::language::Common {
+                file: "/aoc/2024/day 05/main.py".into(),
+                day_folder: "/aoc/2024/day 05".into(),
+                day: 5,
+                year: 2024,
+                root_folder: "/aoc/2024".into(),
+                input_file: "/tmp/input".into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(shell_quote("/aoc/day_01/input"), "/aoc/day_01/input");
+        assert_eq!(shell_quote("day 01"), "'day 01'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let expand = |s| expand_templates(s, &day_args(), true).unwrap();
+
+        assert_eq!(
+            expand("python3 {file}"),
+            "python3 '/aoc/2024/day 05/main.py'"
+        );
+        assert_eq!(expand("{rel:file} {name:day}"), "'day 05/main.py' 'day 05'");
+        assert_eq!(expand("{stem:file} {rel:input}"), "main /tmp/input");
+        assert_eq!(
+            expand("{root} {year} {daynum} {daynum:02}"),
+            "/aoc/2024 2024 5 05"
+        );
+        assert_eq!(expand("{input} {args} {part}"), r"/tmp/input 'it'\''s' x ");
+        assert_eq!(expand("awk '{{print $1}}'"), "awk '{print $1}'");
+        assert_eq!(
+            expand_templates("{day}/bin", &day_args(), false).unwrap(),
+            "/aoc/2024/day 05/bin"
+        );
+
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(expand("{env:PATH}"), shell_quote(&path));
+    }
+
+    #[test]
+    fn test_cargo_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = |manifest: &str| {
+            std::fs::write(dir.path().join("Cargo.toml"), manifest).unwrap();
+            cargo_bin(dir.path())
+        };
+
+        let package = "[package]\nname = \"solution\"\n";
+        assert_eq!(bin(package).unwrap(), "solution");
+        assert_eq!(
+            bin(&format!("{}[[bin]]\nname = \"day5\"\n", package)).unwrap(),
+            "day5"
+        );
+        let bins = "[[bin]]\nname = \"a\"\n[[bin]]\nname = \"b\"\n";
+        assert!(bin(&format!("{}{}", package, bins)).is_err());
+        assert_eq!(
+            bin(&format!("{}default-run = \"b\"\n{}", package, bins)).unwrap(),
+            "b"
+        );
+
+        let mut args = day_args();
+        args.common.day_folder = dir.path().to_path_buf();
+        assert_eq!(
+            expand_templates("{day}/target/release/{bin}", &args, false).unwrap(),
+            format!("{}/target/release/b", dir.path().display())
+        );
+    }
+
+    #[test]
+    fn test_placeholder_errors() {
+        let error = |s| match expand_templates(s, &day_args(), true) {
+            Err(AocError::TemplateError {
+                column, message, ..
+            }) => (column, message),
+            res => panic!("expected template error, got {:?}", res),
+        };
+
+        assert_eq!(error("run {fiel}").0, 5);
+        assert!(error("run {fiel}").1.contains("fiel"));
+        assert!(error("{abs:file}").1.contains("prefix"));
+        assert!(error("{daynum:x}").1.contains("format"));
+        assert!(error("{env:CARGO_AOC_UNSET_VAR}").1.contains("not set"));
+        assert_eq!(error("awk '{{print}}' {file").0, 17);
+        assert_eq!(error("a }").0, 3);
+    }
+
+    #[test]
+    fn test_input_mode_from_toml() {
+        let config = config(
+            r#"
+            [toolchain.awk]
+            ext = "awk"
+            run = "awk -f {file}"
+            input = "stdin"
+
+            [toolchain.py]
+            ext = "py"
+            run = "python3 {file}"
+            "#,
+        );
+
+        assert_eq!(config.toolchain["awk"].input, InputMode::Stdin);
+        assert_eq!(config.toolchain["py"].input, InputMode::Arg);
+        assert!(
+            toml::from_str::<Layer>("[toolchain.x]\next = \"x\"\nrun = \"x\"\ninput = \"file\"\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_extends() {
+        let config = config(
+            r#"
+            [toolchain.py]
+            ext = "py"
+            run = "python3 {file}"
+            env = { PYTHONOPTIMIZE = "1", PYTHONHASHSEED = "0" }
+
+            [toolchain.pypy]
+            extends = "py"
+            run = "pypy3 {file}"
+            env = { PYTHONOPTIMIZE = "2" }
+
+            [toolchain.loop]
+            extends = "loop2"
+
+            [toolchain.loop2]
+            extends = "loop"
+            ext = "x"
+            run = "x"
+
+            [toolchain.orphan]
+            extends = "missing"
+            "#,
+        );
+
+        let pypy = &config.toolchain["pypy"];
+        assert_eq!(pypy.run, "pypy3 {file}");
+        assert_eq!(pypy.ext, "py");
+        assert_eq!(pypy.env["PYTHONOPTIMIZE"], "2");
+        assert_eq!(pypy.env["PYTHONHASHSEED"], "0");
+        assert!(!config.toolchain.contains_key("loop"));
+        assert!(!config.toolchain.contains_key("orphan"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let layer = toml::from_str::<Layer>(
+            r#"
+            [extensions]
+            py = "go"
+            js = "node"
+            x = "loop"
+
+            [toolchain.go]
+            ext = "go"
+            run = "go run {file}"
+
+            [toolchain.loop]
+            extends = "loop"
+
+            [toolchain.orphan]
+            extends = "missing"
+
+            [toolchain.norun]
+            ext = "x"
+            "#,
+        )
+        .unwrap();
+        let (_, errors) = Config::from_layers([layer]);
+
+        let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        let prefix = |name| format!("Invalid toolchain `{}` in .languages.toml: ", name);
+        assert_eq!(
+            errors,
+            [
+                prefix("loop") + "`loop` extends itself",
+                prefix("norun") + "missing `run`",
+                prefix("orphan") + "extends unknown toolchain `missing`",
+                prefix("node") + "picked for .js files, but it is not defined",
+                prefix("go") + "picked for .py files, but it runs .go files",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extensions() {
+        let config = config(
+            r#"
+            [extensions]
+            py = "pypy"
+
+            [toolchain.python]
+            ext = "py"
+            run = "python3 {file}"
+
+            [toolchain.pypy]
+            extends = "python"
+            run = "pypy3 {file}"
+
+            [toolchain.node]
+            ext = "js"
+            run = "node {file}"
+
+            [toolchain.bun]
+            extends = "node"
+            run = "bun {file}"
+            "#,
+        );
+
+        assert_eq!(config.extensions["py"], "pypy");
+        assert_eq!(config.extensions["js"], "bun");
+        assert_eq!(config.runners().len(), 2);
+    }
+
+    #[test]
+    fn test_markers() {
+        let config = config(
+            r#"
+            [toolchain.go]
+            ext = "go"
+            run = "go run {file}"
+
+            [toolchain.go-module]
+            extends = "go"
+            markers = ["go.mod"]
+            run = "go run ."
+
+            [toolchain.solver]
+            extends = "go-module"
+            entry = "solution"
+            needs_entry = true
+            "#,
+        );
+
+        assert_eq!(config.extensions["go"], "go");
+        assert_eq!(config.toolchain["go"].entry, "main");
+        assert_eq!(config.toolchain["solver"].entry, "solution");
+        assert_eq!(config.toolchain["solver"].markers, ["go.mod"]);
+        assert!(!config.toolchain["go-module"].needs_entry);
+        assert!(config.toolchain["solver"].needs_entry);
+        assert_eq!(config.project_runners().len(), 2);
+    }
+
+    #[test]
+    fn test_programs() {
+        let config = config(
+            r#"
+            [toolchain.c]
+            ext = "c"
+            run = "mkdir -p target && CFLAGS=-g cc -o target/main {file} && ./target/main"
+            compile = { build = "gcc -O2 {file} 2>&1 | tee log", execute = "{day}/target/main" }
+            "#,
+        );
+
+        assert_eq!(
+            config.toolchain["c"].programs(),
+            ["mkdir", "cc", "gcc", "tee"]
+        );
+    }
+
+    #[test]
+    fn test_builtin() {
+        let layer = toml::from_str(include_str!("../../.languages.toml")).unwrap();
+        let (config, errors) = Config::from_layers([layer]);
+
+        assert!(errors.is_empty());
+        assert_eq!(config.toolchain.len(), 25);
+        assert_eq!(config.extensions["py"], "python");
+        assert_eq!(config.extensions["rs"], "rust");
+        assert_eq!(config.extensions["go"], "go");
+        assert_eq!(config.extensions["c"], "c");
+        assert_eq!(config.toolchain["pypy"].ext, "py");
+        assert_eq!(config.toolchain["go-module"].markers, ["go.mod"]);
+        assert_eq!(config.project_runners().len(), 6);
+        assert_eq!(
+            config.toolchain["rust"].commands(),
+            ["run", "release", "test", "lint", "fmt"]
+        );
+        assert_eq!(config.toolchain["julia"].commands(), ["run", "release"]);
+    }
+
+    #[test]
+    fn test_layers() {
+        let layer = |s| toml::from_str::<Layer>(s).unwrap();
+        let config = Config::from_layers([
+            layer("[toolchain.rust]\next = \"rs\"\nrun = \"cargo run\"\ndir = \"{day}\""),
+            layer("[toolchain.rust]\nrun = \"cargo run -q\"\nenv = { A = \"1\" }"),
+            layer("release = true\n[toolchain.rust]\nenv = { B = \"{daynum}\" }"),
+        ])
+        .0;
+        assert!(config.release);
+
+        let rust = &config.toolchain["rust"];
+        assert_eq!(rust.run, "cargo run -q");
+        assert_eq!(rust.dir.as_deref(), Some("{day}"));
+        assert_eq!(rust.env.len(), 2);
+    }
 }
@@ -1,8 +1,8 @@
 mod config;
 mod r#trait;
 
-pub use config::Config;
+pub use config::{Config, Layer};
 
-pub use r#trait::{Common, Compile, Runner, RunningArgs};
+pub use r#trait::{Common, Compile, Mode, Runner, RunningArgs, Tool};
 mod register;
-pub use register::REGISTER;
+pub use register::{Main, Register};
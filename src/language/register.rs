@@ -1,8 +1,9 @@
-use std::sync::LazyLock;
+use std::path::{Path, PathBuf};
 
 use crate::{
-    language::Compile,
-    util::{self, file::get_root_path},
+    error::AocError,
+    language::{Compile, Config, r#trait::Ext},
+    util::file::{find_file, get_file_lang, get_supported_languages},
 };
 
 use super::Runner;
@@ -10,6 +11,18 @@ use super::Runner;
 pub struct Register {
     langs: Vec<Box<dyn Runner + Sync + Send>>,
     compilers: Vec<Box<dyn Compile + Sync + Send>>,
+    /// The toolchains with markers, which take precedence for their
+    /// extension in a day folder with one of their markers
+    projects: Vec<Box<dyn Runner + Sync + Send>>,
+    project_compilers: Vec<Box<dyn Compile + Sync + Send>>,
+}
+
+/// The file a day is run with, and the language (extension) of the
+/// toolchain running it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Main {
+    pub lang: String,
+    pub file: PathBuf,
 }
 
 impl Register {
@@ -17,21 +30,44 @@ impl Register {
         Self {
             langs: Vec::new(),
             compilers: Vec::new(),
+            projects: Vec::new(),
+            project_compilers: Vec::new(),
+        }
+    }
+
+    fn from_config(config: Config) -> Self {
+        let mut r = Register::new();
+        for runner in config.runners() {
+            r.register(runner);
+        }
+
+        for compiler in config.compilers() {
+            r.register_compiler(compiler);
+        }
+
+        for runner in config.project_runners() {
+            r.projects.push(Box::new(runner));
+        }
+        for compiler in config.project_compilers() {
+            r.project_compilers.push(Box::new(compiler));
         }
+        r
     }
 
-    pub fn runner_exts(&self) -> Vec<String> {
-        self.langs
-            .iter()
-            .map(|l| l.extension().to_string())
-            .collect()
+    /// The toolchains of the year, with the `.languages.toml` of the day
+    /// folder if it has one
+    pub fn for_day(root: &Path, day_folder: &Path) -> Result<Register, AocError> {
+        get_supported_languages(root, Some(day_folder)).map(Register::from_config)
     }
 
-    pub fn compiler_exts(&self) -> Vec<String> {
-        self.compilers
-            .iter()
-            .map(|l| l.extension().to_string())
-            .collect()
+    /// The main file of every language the day is implemented in
+    pub fn mains(&self, day_folder: &Path) -> Vec<Main> {
+        find_mains(&self.projects, &self.langs, day_folder)
+    }
+
+    /// Like [`Register::mains`], for the languages that can be compiled
+    pub fn compiled_mains(&self, day_folder: &Path) -> Vec<Main> {
+        find_mains(&self.project_compilers, &self.compilers, day_folder)
     }
 
     pub fn register<L: Runner + Sync + Send + 'static>(&mut self, lang: L) {
@@ -40,36 +76,119 @@ impl Register {
     pub fn register_compiler<C: Compile + Sync + Send + 'static>(&mut self, c: C) {
         self.compilers.push(Box::new(c));
     }
-    pub fn by_extension<'a>(&'a self, ext: &str) -> Option<&'a (dyn Runner + Sync + Send)> {
-        self.langs
-            .iter()
-            .find(|c| c.extension() == ext)
-            .map(|b| &**b)
+    /// The runner for a main file of the day, see [`Register::mains`]
+    pub fn by_file<'a>(
+        &'a self,
+        day_folder: &Path,
+        file: &Path,
+    ) -> Result<&'a (dyn Runner + Sync + Send), AocError> {
+        find_toolchain(&self.projects, &self.langs, day_folder, file)
+            .ok_or_else(|| AocError::UnsupportedLanguage(get_file_lang(file)))
     }
-    pub fn compiler_by_extension<'a>(
+
+    pub fn compiler_by_file<'a>(
         &'a self,
-        ext: &str,
+        day_folder: &Path,
+        file: &Path,
     ) -> Option<&'a (dyn Compile + Sync + Send)> {
-        self.compilers
-            .iter()
-            .find(|c| c.extension() == ext)
-            .map(|b| &**b)
+        find_toolchain(&self.project_compilers, &self.compilers, day_folder, file)
+    }
+
+    /// The language of a main file of the day, which is the extension of
+    /// the toolchain running it
+    pub fn lang(&self, day_folder: &Path, file: &Path) -> String {
+        match self.by_file(day_folder, file) {
+            Ok(runner) => runner.extension().to_owned(),
+            Err(_) => get_file_lang(file),
+        }
     }
 }
 
-pub static REGISTER: LazyLock<Register> = LazyLock::new(|| {
-    let mut r = Register::new();
+/// The first marker of `toolchain` in the day folder
+fn marker<T: Ext + ?Sized>(toolchain: &T, day_folder: &Path) -> Option<PathBuf> {
+    toolchain
+        .markers()
+        .iter()
+        .map(|marker| day_folder.join(marker))
+        .find(|marker| marker.is_file())
+}
 
-    if let Ok(root) = get_root_path() {
-        let config = util::file::get_supported_languages(&root);
-        for runner in config.runners() {
-            r.register(runner);
+fn entry<T: Ext + ?Sized>(toolchain: &T, day_folder: &Path) -> Option<PathBuf> {
+    let exts = [toolchain.extension().to_owned()];
+    find_file(day_folder, toolchain.entry(), Some(&exts)).filter(|file| file.extension().is_some())
+}
+
+/// A toolchain with a marker in the day folder runs its language, with its
+/// entry file or else the marker itself, e.g for `cargo run` with any binary
+/// name. The others run the entry file for their extension
+fn find_mains<T: Ext + ?Sized>(
+    projects: &[Box<T>],
+    active: &[Box<T>],
+    day_folder: &Path,
+) -> Vec<Main> {
+    let mut mains: Vec<Main> = Vec::new();
+    let found = |mains: &[Main], lang: &str| mains.iter().any(|main| main.lang == lang);
+
+    for toolchain in projects {
+        if found(&mains, toolchain.extension()) {
+            continue;
         }
+        if let Some(marker) = marker(&**toolchain, day_folder) {
+            let file = match entry(&**toolchain, day_folder) {
+                Some(file) => file,
+                None if toolchain.needs_entry() => continue,
+                None => marker,
+            };
+            mains.push(Main {
+                lang: toolchain.extension().to_owned(),
+                file,
+            });
+        }
+    }
 
-        for compiler in config.compilers() {
-            r.register_compiler(compiler);
+    for toolchain in active {
+        if found(&mains, toolchain.extension()) {
+            continue;
+        }
+        if let Some(file) = entry(&**toolchain, day_folder) {
+            mains.push(Main {
+                lang: toolchain.extension().to_owned(),
+                file,
+            });
         }
     }
 
-    r
-});
+    mains.sort();
+    mains
+}
+
+fn find_toolchain<'a, T: Ext + ?Sized>(
+    projects: &'a [Box<T>],
+    active: &'a [Box<T>],
+    day_folder: &Path,
+    file: &Path,
+) -> Option<&'a T> {
+    let ext = get_file_lang(file);
+    let is_marker = |toolchain: &T| {
+        file.file_name().is_some_and(|name| {
+            toolchain
+                .markers()
+                .iter()
+                .any(|marker| name == marker.as_str())
+        })
+    };
+
+    projects
+        .iter()
+        .map(Box::as_ref)
+        .find(|toolchain| {
+            (toolchain.extension() == ext || is_marker(toolchain))
+                && marker(*toolchain, day_folder).is_some()
+        })
+        .or_else(|| {
+            active
+                .iter()
+                .map(Box::as_ref)
+                .find(|toolchain| toolchain.extension() == ext)
+        })
+}
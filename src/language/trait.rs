@@ -1,25 +1,84 @@
 use std::path::PathBuf;
 
-use crate::error::AocError;
+use crate::{error::AocError, util::Task};
 
 pub trait Ext {
     fn extension(&self) -> &str;
+
+    /// The name of the file to run, without the extension
+    fn entry(&self) -> &str;
+
+    /// Project files that identify the toolchain in a day folder
+    fn markers(&self) -> &[String];
+
+    /// Whether a day folder with a marker also needs the entry file
+    fn needs_entry(&self) -> bool;
 }
 
 pub trait Runner: Ext {
     fn execute(&self, args: RunningArgs) -> Result<duct::Expression, AocError>;
+
+    /// Another command for the day, if the toolchain has it
+    fn tool(&self, _tool: Tool, _args: RunningArgs) -> Option<Result<duct::Expression, AocError>> {
+        None
+    }
+}
+
+/// Commands of a toolchain besides running the solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Test,
+    Lint,
+    LintFix,
+    Fmt,
+    FmtFix,
+}
+
+impl std::fmt::Display for Tool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tool::Test => write!(f, "test"),
+            Tool::Lint => write!(f, "lint"),
+            Tool::LintFix => write!(f, "lint fix"),
+            Tool::Fmt => write!(f, "fmt"),
+            Tool::FmtFix => write!(f, "fmt fix"),
+        }
+    }
 }
 
 pub trait Compile: Ext {
     fn compile(&self, args: RunningArgs) -> Result<duct::Expression, AocError>;
 }
 
+/// What the solution is run for, exported as `AOC_MODE`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Run,
+    /// Running with the `test` input
+    Test,
+    Tally,
+    Bench,
+}
+
+impl Mode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mode::Run => "run",
+            Mode::Test => "test",
+            Mode::Tally => "tally",
+            Mode::Bench => "bench",
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Default, Debug)]
 pub struct Common {
     pub file: PathBuf,
     pub day_folder: PathBuf,
     pub day: i32,
+    pub year: i32,
     pub root_folder: PathBuf,
     pub input_file: PathBuf,
 }
@@ -29,5 +88,29 @@ pub struct Common {
 pub struct RunningArgs {
     pub arguments: Vec<String>,
     pub release: bool,
+    /// Only run this part, see `run --part`
+    pub part: Option<Task>,
+    pub mode: Mode,
     pub common: Common,
 }
+
+impl RunningArgs {
+    /// Environment variables exported to every solution, so that solutions
+    /// in any language can adapt to how they are run
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("AOC_YEAR", self.common.year.to_string()),
+            ("AOC_DAY", self.common.day.to_string()),
+            ("AOC_INPUT", self.common.input_file.display().to_string()),
+            ("AOC_MODE", self.mode.as_str().to_owned()),
+            (
+                "TASKUNIT",
+                std::env::var("TASKUNIT").unwrap_or("ms".to_owned()),
+            ),
+        ];
+        if let Some(part) = self.part {
+            env.push(("AOC_PART", part.number().to_string()));
+        }
+        env
+    }
+}
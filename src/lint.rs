@@ -0,0 +1,198 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    process::ExitCode,
+};
+
+use clap::ArgMatches;
+use serde::Deserialize;
+use table_generator::{Column, Table};
+
+use crate::{
+    error::AocError,
+    language::{Mode, Tool},
+    run::start_tool,
+    util::{
+        file::{get_root_path, get_year_from_path},
+        get_days, get_lang,
+    },
+};
+
+/// Diagnostics of a day, counted when the command prints cargo's json
+/// messages, e.g with `--message-format=json`
+#[derive(Debug, Default)]
+struct Counts {
+    warnings: usize,
+    errors: usize,
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    level: String,
+    rendered: Option<String>,
+    spans: Vec<serde_json::Value>,
+}
+
+/// Prints the output of the command, with cargo's json messages printed as
+/// the rendered diagnostic
+fn print_messages(reader: impl Read) -> Option<Counts> {
+    let mut counts = None;
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        let Ok(msg) = serde_json::from_str::<CargoMessage>(&line) else {
+            println!("{}", line);
+            continue;
+        };
+
+        let counts = counts.get_or_insert_with(Counts::default);
+        let Some(diagnostic) = msg.message.filter(|_| msg.reason == "compiler-message") else {
+            continue;
+        };
+        if let Some(rendered) = &diagnostic.rendered {
+            print!("{}", rendered);
+        }
+
+        // Summaries like "aborting due to 2 previous errors" have no spans
+        if diagnostic.spans.is_empty() {
+            continue;
+        }
+        match diagnostic.level.as_str() {
+            "warning" => counts.warnings += 1,
+            "error" => counts.errors += 1,
+            _ => {}
+        }
+    }
+    counts
+}
+
+struct Checked {
+    passed: bool,
+    counts: Option<Counts>,
+}
+
+async fn check_day(
+    root: &Path,
+    day: u32,
+    tool: Tool,
+    lang: Option<&str>,
+) -> Result<Checked, AocError> {
+    let child = start_tool(root, day, tool, Mode::Run, lang).await?;
+    let counts = print_messages(child.output());
+    let (status, _) = child.wait()?;
+
+    Ok(Checked {
+        passed: status.success(),
+        counts,
+    })
+}
+
+fn print_summary(tool: Tool, results: &[(u32, Result<Checked, AocError>)]) {
+    let show_counts = results
+        .iter()
+        .any(|(_, res)| res.as_ref().is_ok_and(|c| c.counts.is_some()));
+    let last_col = if show_counts { 3 } else { 1 };
+
+    let mut table = Table::new(format!("cargo aoc {}", tool));
+    let mut day_col = Column::new("Day");
+    let mut result_col = Column::new("Result");
+    let mut warnings_col = Column::new("Warnings");
+    let mut errors_col = Column::new("Errors");
+
+    for (i, (day, res)) in results.iter().enumerate() {
+        day_col.add_row(vec![day.to_string()]);
+        match res {
+            Ok(checked) => {
+                result_col.add_row(vec![if checked.passed { "ok" } else { "FAILED" }]);
+                let count = |f: fn(&Counts) -> usize| {
+                    checked
+                        .counts
+                        .as_ref()
+                        .map(f)
+                        .map(|n| n.to_string())
+                        .unwrap_or("NA".to_string())
+                };
+                warnings_col.add_row(vec![count(|c| c.warnings)]);
+                errors_col.add_row(vec![count(|c| c.errors)]);
+            }
+            Err(err) => {
+                table.add_span(i, 1..=last_col, err.to_string().replace('\n', " "));
+
+                // Need to add a dummy column. the span will override it.
+                result_col.add_row(vec![""]);
+                warnings_col.add_row(vec![""]);
+                errors_col.add_row(vec![""]);
+            }
+        }
+    }
+
+    table.add_column(day_col);
+    table.add_column(result_col);
+    if show_counts {
+        table.add_column(warnings_col);
+        table.add_column(errors_col);
+    }
+    println!("{}", table);
+}
+
+fn exit_code(ok: bool) -> ExitCode {
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+async fn check(matches: &ArgMatches, tool: Tool) -> Result<ExitCode, AocError> {
+    let root = get_root_path()?;
+    let year = get_year_from_path(&root)?;
+    let days = get_days(matches, year)?;
+    let lang = get_lang(matches);
+
+    if let [day] = days.as_slice() {
+        let checked = check_day(&root, *day, tool, lang).await?;
+        if let Some(counts) = checked.counts {
+            println!("{} warnings, {} errors", counts.warnings, counts.errors);
+        }
+        return Ok(exit_code(checked.passed));
+    }
+
+    let mut results = Vec::new();
+    for day in days {
+        println!("\n\x1b[1m--- Day {:02} ---\x1b[0m", day);
+        let res = check_day(&root, day, tool, lang).await;
+        if let Err(err) = &res {
+            println!("{}", err);
+        }
+        results.push((day, res));
+    }
+
+    print_summary(tool, &results);
+    Ok(exit_code(
+        results
+            .iter()
+            .all(|(_, res)| res.as_ref().is_ok_and(|c| c.passed)),
+    ))
+}
+
+pub async fn lint(matches: &ArgMatches) -> Result<ExitCode, AocError> {
+    let tool = if matches.get_flag("fix") {
+        Tool::LintFix
+    } else {
+        Tool::Lint
+    };
+    check(matches, tool).await
+}
+
+pub async fn fmt(matches: &ArgMatches) -> Result<ExitCode, AocError> {
+    let tool = if matches.get_flag("fix") {
+        Tool::FmtFix
+    } else {
+        Tool::Fmt
+    };
+    check(matches, tool).await
+}
@@ -1,29 +1,55 @@
-use crate::util::get_day_argument;
+use crate::util::{get_day_argument, get_days_arguments, lang_argument};
 use chrono::Datelike;
 use clap::{Arg, Command, builder::OsStr};
 use error::AocError;
+use std::process::ExitCode;
 mod assert;
 #[cfg(feature = "bench")]
 mod bench;
-mod clippy;
 mod error;
 mod language;
+mod lint;
+mod provider;
+mod report;
 mod run;
 mod setup;
+mod supervise;
 #[cfg(feature = "tally")]
 mod tally;
 mod task_config;
 mod test;
 mod token;
+mod toolchains;
 mod util;
+#[cfg(feature = "watch")]
+mod watch;
 
 #[tokio::main]
-async fn main() -> Result<(), AocError> {
+async fn main() -> ExitCode {
+    let code = match cli().await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    };
+    util::file::remove_temp_inputs();
+    code
+}
+
+async fn cli() -> Result<ExitCode, AocError> {
     dotenv::dotenv().ok();
     let mut cmd = Command::new("cargo-aoc")
         .author("Sebastian, sebastian@lyngjohansen.com")
         .author("Sivert, sivert-joh@hotmail.com")
         .arg(Arg::new("dummy").hide(true))
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .global(true)
+                .action(clap::ArgAction::SetTrue)
+                .help("Fail on invalid configuration files instead of warning and ignoring them"),
+        )
         .subcommand(
             clap::command!("setup")
                 .arg(
@@ -38,51 +64,122 @@ async fn main() -> Result<(), AocError> {
                 ),
         )
         .subcommand(
-            clap::command!("clippy")
+            clap::command!("lint")
+                .visible_alias("clippy")
                 .disable_version_flag(true)
-                .about("Run cargo clippy on the specified day")
-                .args([
-                    get_day_argument().help("Day to check"),
+                .about("Lint the specified day with the lint command of its toolchain")
+                .args(get_days_arguments("lint"))
+                .arg(lang_argument())
+                .arg(
                     Arg::new("fix")
                         .long("fix")
                         .required(false)
                         .action(clap::ArgAction::SetTrue)
-                        .help("Fixes the issues clippy warns about"),
-                ]),
+                        .help("Fixes the issues the linter warns about"),
+                ),
+        )
+        .subcommand(
+            clap::command!("fmt")
+                .disable_version_flag(true)
+                .about("Check the formatting of the specified day with the fmt command of its toolchain")
+                .args(get_days_arguments("check"))
+                .arg(lang_argument())
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Formats the day instead of only checking it"),
+                ),
         )
         .subcommand(
             clap::command!("run")
                 .visible_alias("r")
+                .args(get_days_arguments("run"))
                 .args([
-                    get_day_argument().help("Day to run"),
                     Arg::new("test")
                         .short('t')
                         .long("test")
                         .required(false)
                         .action(clap::ArgAction::SetTrue)
                         .help("Run the day with the \"test\" file"),
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .required(false)
+                        .conflicts_with("test")
+                        .help("Run the day with the given input file, or - to read it from stdin"),
+                    Arg::new("input-text")
+                        .long("input-text")
+                        .required(false)
+                        .conflicts_with_all(["test", "input"])
+                        .help("Run the day with the given text as input"),
                     Arg::new("assert")
                         .short('a')
                         .long("assert")
                         .required(false)
                         .action(clap::ArgAction::SetTrue)
                         .help("Asserts that the answers are still correct after submitting"),
+                    Arg::new("part")
+                        .short('p')
+                        .long("part")
+                        .required(false)
+                        .value_parser(["1", "2"])
+                        .help("Only run part 1 or 2. Sets AOC_PART for the solution"),
+                    Arg::new("release")
+                        .short('r')
+                        .long("release")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Run the day with optimisations"),
+                    Arg::new("debug")
+                        .long("debug")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("release")
+                        .help("Run the day without optimisations, even if release is the default"),
+                    lang_argument(),
+                    Arg::new("all-langs")
+                        .long("all-langs")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("lang")
+                        .help("Run the implementation in every language and compare the answers"),
+                    #[cfg(feature = "watch")]
+                    Arg::new("watch")
+                        .short('w')
+                        .long("watch")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Rerun the day every time a file in the day folder changes"),
                     #[cfg(feature = "submit")]
                     Arg::new("submit")
                         .short('S')
                         .long("submit")
                         .required(false)
-                        .help("Submit answer")
-                        .conflicts_with("test"),
+                        .num_args(0..=1)
+                        .help("Submit the answer to part 1 or 2, or the part given with --part")
+                        .conflicts_with_all(["test", "input", "input-text", "all-langs"]),
                     Arg::new("args")
                         .num_args(1..)
                         .trailing_var_arg(true)
                         .allow_hyphen_values(true)
                         .help("Arguments after -- will passed to cargo"),
                 ])
+                .args(supervise::limit_arguments())
+                .arg(report::format_argument())
                 .about("Runs the given day"),
         )
-        .subcommand(clap::command!("test").args([get_day_argument().help("Day to run tests for")]))
+        .subcommand(
+            clap::command!("test")
+                .about("Run the tests of the given day with the test command of its toolchain")
+                .args(get_days_arguments("test"))
+                .arg(lang_argument()),
+        )
+        .subcommand(
+            Command::new("languages")
+                .about("List the configured toolchains, their commands and whether their programs are installed"),
+        )
         .subcommand(
             Command::new("token")
                 .about("Get or set the session token used to communicate with the AOC servers")
@@ -99,9 +196,30 @@ async fn main() -> Result<(), AocError> {
                         .exclusive(true)
                         .num_args(0)
                         .help("Print the current session token, if any"),
+                    #[cfg(feature = "browser")]
+                    Arg::new("from-browser")
+                        .long("from-browser")
+                        .exclusive(true)
+                        .value_parser(util::browser::Browser::NAMES)
+                        .help("Import the session token from a local browser profile"),
                 ]),
         );
 
+    #[cfg(all(feature = "submit", feature = "watch"))]
+    {
+        cmd = cmd.mut_subcommand("run", |run| {
+            run.mut_arg("submit", |arg| arg.conflicts_with("watch"))
+        });
+    }
+
+    #[cfg(feature = "watch")]
+    {
+        cmd = cmd.mut_subcommand("run", |run| {
+            run.mut_arg("format", |arg| arg.conflicts_with("watch"))
+                .mut_arg("all-langs", |arg| arg.conflicts_with("watch"))
+        });
+    }
+
     #[cfg(feature = "tally")]
     {
         cmd = cmd.subcommand(
@@ -115,7 +233,18 @@ async fn main() -> Result<(), AocError> {
                         .long("num-runs")
                         .help("Number of runs")
                         .default_value("10"),
-                ),
+                )
+                .arg(lang_argument())
+                .arg(
+                    Arg::new("compare-langs")
+                        .long("compare-langs")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("lang")
+                        .help("Tally every language a day is implemented in, with a column per language"),
+                )
+                .args(supervise::limit_arguments())
+                .arg(report::format_argument()),
         );
     }
 
@@ -137,14 +266,18 @@ async fn main() -> Result<(), AocError> {
 
     let help = cmd.render_help();
     let matches = cmd.get_matches();
+    util::file::set_strict(matches.get_flag("strict"));
+    supervise::forward_ctrl_c();
     match matches.subcommand() {
         Some(("setup", matches)) => setup::setup(matches)
             .await
             .expect("Couldn't setup project properly"),
-        Some(("run", matches)) => run::run(matches).await?,
-        Some(("test", matches)) => test::test(matches).await?,
+        Some(("run", matches)) => return Ok(run::run(matches).await?.into()),
+        Some(("test", matches)) => return test::test(matches).await,
         Some(("token", matches)) => token::token(matches).await?,
-        Some(("clippy", matches)) => clippy::clippy(matches).await?,
+        Some(("languages", _)) => toolchains::languages()?,
+        Some(("lint", matches)) => return lint::lint(matches).await,
+        Some(("fmt", matches)) => return lint::fmt(matches).await,
 
         #[cfg(feature = "bench")]
         Some(("bench", matches)) => bench::bench(matches).await?,
@@ -155,5 +288,5 @@ async fn main() -> Result<(), AocError> {
             println!("{}", help);
         }
     }
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
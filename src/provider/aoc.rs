@@ -0,0 +1,147 @@
+use chrono::Datelike;
+use futures::{FutureExt, future::BoxFuture};
+use reqwest::StatusCode;
+
+use super::{Input, Provider, PuzzleInfo};
+#[cfg(feature = "submit")]
+use crate::util::{Task, submit::Submission};
+use crate::{
+    error::AocError,
+    util::{page::PuzzlePage, request::AocRequest},
+};
+
+const BASE_URL: &str = "https://adventofcode.com";
+
+pub struct AdventOfCode;
+
+impl AdventOfCode {
+    async fn inputs(year: i32, day: u32) -> Result<Vec<Input>, AocError> {
+        let url = format!("{}/{}/day/{}/input", BASE_URL, year, day);
+        let res = AocRequest::new()?.get(url).await?;
+
+        if res.status() != StatusCode::OK {
+            return Err(AocError::DownloadError(format!(
+                "Couldn't download input for year: {} and day: {}",
+                year, day
+            )));
+        }
+
+        // Both parts share the input
+        let bytes = res.bytes().await?;
+        Ok(vec![Input {
+            part: None,
+            content: bytes.to_vec(),
+        }])
+    }
+
+    async fn info(year: i32, day: u32) -> Result<PuzzleInfo, AocError> {
+        let url = format!("{}/{}/day/{}", BASE_URL, year, day);
+
+        let res = AocRequest::new()?.get(&url).await?;
+        if !res.status().is_success() {
+            return Err(AocError::ApiError(format!(
+                "Could not get day info from {}: {}",
+                url,
+                res.status()
+            )));
+        }
+
+        let text = res.text().await?;
+        Ok(PuzzleInfo::from(PuzzlePage::parse(&text)?))
+    }
+
+    async fn verify_token() -> Result<(), AocError> {
+        let url = format!("{}/2015/day/1/input", BASE_URL);
+        let res = AocRequest::new()?.get(url).await;
+
+        match res {
+            Ok(res) => match res.status() {
+                StatusCode::OK => Ok(()),
+                _ => Err(AocError::InvalidTokenError(res.status().to_string())),
+            },
+            Err(AocError::ReqwestError(err)) => Err(AocError::ApiError(err.to_string())),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(feature = "submit")]
+    async fn submit(year: i32, day: u32, task: Task, answer: &str) -> Result<Submission, AocError> {
+        use std::collections::HashMap;
+
+        use sanitize_html::rules::predefined::DEFAULT;
+
+        let parse_and_sanitize_output = |output: &str| {
+            let start = output.find("<article><p>")?;
+            let end = output.find("</p></article>")?;
+            let body = &output[start..end];
+            sanitize_html::sanitize_str(&DEFAULT, body).ok()
+        };
+
+        let url = format!("{}/{}/day/{}/answer", BASE_URL, year, day);
+
+        let mut form = HashMap::new();
+        form.insert("level", task.number().to_string());
+        form.insert("answer", answer.to_owned());
+        let res = AocRequest::new()?.post(&url, &form).await?;
+
+        let text = &res.text().await?;
+        let message = parse_and_sanitize_output(text).ok_or(AocError::SanitizeHtml)?;
+        Ok(Submission {
+            accepted: message.contains("That's the right answer"),
+            message,
+        })
+    }
+}
+
+impl Provider for AdventOfCode {
+    fn name(&self) -> &str {
+        "aoc"
+    }
+
+    fn days(&self, year: i32) -> u32 {
+        // From 2025 and onwards there are only 12 days
+        if year >= 2025 { 12 } else { 25 }
+    }
+
+    fn calendar(&self, year: i32) -> Result<Vec<u32>, AocError> {
+        let now = chrono::Utc::now();
+        let last_day = self.days(year);
+
+        if year < 2015 || year > now.year() {
+            return Err(AocError::InvalidYear);
+        }
+
+        if year == now.year() {
+            if now.month() == 12 {
+                Ok((1..=now.day().min(last_day)).collect())
+            } else {
+                Err(AocError::InvalidMonth)
+            }
+        } else {
+            Ok((1..=last_day).collect())
+        }
+    }
+
+    fn inputs(&self, year: i32, day: u32) -> BoxFuture<'_, Result<Vec<Input>, AocError>> {
+        AdventOfCode::inputs(year, day).boxed()
+    }
+
+    fn info(&self, year: i32, day: u32) -> BoxFuture<'_, Result<PuzzleInfo, AocError>> {
+        AdventOfCode::info(year, day).boxed()
+    }
+
+    fn verify_token(&self) -> BoxFuture<'_, Result<(), AocError>> {
+        AdventOfCode::verify_token().boxed()
+    }
+
+    #[cfg(feature = "submit")]
+    fn submit<'a>(
+        &'a self,
+        year: i32,
+        day: u32,
+        task: Task,
+        answer: &'a str,
+    ) -> BoxFuture<'a, Result<Submission, AocError>> {
+        AdventOfCode::submit(year, day, task, answer).boxed()
+    }
+}
@@ -0,0 +1,77 @@
+mod aoc;
+mod r#trait;
+
+use std::path::{Path, PathBuf};
+
+pub use aoc::AdventOfCode;
+pub use r#trait::{Input, PartInfo, Provider, PuzzleInfo};
+
+use crate::{error::AocError, util::user_config::UserConfig};
+
+static PROVIDERS: &[&dyn Provider] = &[&AdventOfCode];
+
+/// The provider selected with `AOC_PROVIDER` or `provider` in the global
+/// config, defaulting to Advent of Code
+pub fn get_provider() -> Result<&'static dyn Provider, AocError> {
+    let name = match dotenv::var("AOC_PROVIDER") {
+        Ok(name) => Some(name),
+        Err(_) => UserConfig::load()?.provider,
+    }
+    .unwrap_or_else(|| AdventOfCode.name().to_owned());
+
+    PROVIDERS
+        .iter()
+        .copied()
+        .find(|p| p.name() == name)
+        .ok_or(AocError::UnsupportedProvider(name))
+}
+
+/// The file name of the input for `part`, or of the input shared by all parts
+pub fn input_name(part: Option<u8>) -> String {
+    match part {
+        Some(part) => format!("input_{}", part),
+        None => "input".to_owned(),
+    }
+}
+
+/// The downloaded input for `part` in `dir`, which is the one of the part if
+/// the provider has an input per part, and else the shared one
+pub fn input_path(dir: &Path, part: Option<u8>) -> PathBuf {
+    let own = dir.join(input_name(Some(part.unwrap_or(1))));
+    if own.exists() {
+        own
+    } else {
+        dir.join(input_name(None))
+    }
+}
+
+/// Downloads the inputs of the day into `dir`
+pub async fn download_inputs(
+    provider: &dyn Provider,
+    year: i32,
+    day: u32,
+    dir: &Path,
+) -> Result<(), AocError> {
+    for input in provider.inputs(year, day).await? {
+        tokio::fs::write(dir.join(input_name(input.part)), input.content).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{input_name, input_path};
+
+    #[test]
+    fn test_input_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+
+        assert_eq!(input_path(dir, Some(2)), dir.join("input"));
+        for part in 1..=3 {
+            std::fs::write(dir.join(input_name(Some(part))), "").unwrap();
+        }
+        assert_eq!(input_path(dir, None), dir.join("input_1"));
+        assert_eq!(input_path(dir, Some(3)), dir.join("input_3"));
+    }
+}
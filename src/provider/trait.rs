@@ -0,0 +1,62 @@
+use futures::future::BoxFuture;
+
+use crate::error::AocError;
+#[cfg(feature = "submit")]
+use crate::util::{Task, submit::Submission};
+
+/// A part of a puzzle, and the answer the user has submitted for it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartInfo {
+    pub answer: Option<String>,
+    /// Whether the user has the star of the part, which some parts give
+    /// without an answer, e.g the second part of the last day
+    pub solved: bool,
+}
+
+/// The title of a puzzle and its parts, e.g two for Advent of Code and three
+/// for Everybody Codes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleInfo {
+    pub title: String,
+    pub parts: Vec<PartInfo>,
+}
+
+/// An input of a puzzle, shared by all parts unless it is for `part`
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub part: Option<u8>,
+    pub content: Vec<u8>,
+}
+
+/// A website hosting the puzzles, e.g adventofcode.com
+pub trait Provider: Sync + Send {
+    /// Name used to select the provider with `AOC_PROVIDER` or `provider` in
+    /// the global config
+    fn name(&self) -> &str;
+
+    /// The number of days of the year
+    fn days(&self, year: i32) -> u32;
+
+    /// The days that are unlocked for the year
+    fn calendar(&self, year: i32) -> Result<Vec<u32>, AocError>;
+
+    /// The inputs of the day, either a single one shared by all parts or one
+    /// per part
+    fn inputs(&self, year: i32, day: u32) -> BoxFuture<'_, Result<Vec<Input>, AocError>>;
+
+    /// Title of the day and the answers the user has submitted
+    fn info(&self, year: i32, day: u32) -> BoxFuture<'_, Result<PuzzleInfo, AocError>>;
+
+    /// Checks that the session token is accepted
+    fn verify_token(&self) -> BoxFuture<'_, Result<(), AocError>>;
+
+    /// Submits the answer and returns whether it was accepted
+    #[cfg(feature = "submit")]
+    fn submit<'a>(
+        &'a self,
+        year: i32,
+        day: u32,
+        task: Task,
+        answer: &'a str,
+    ) -> BoxFuture<'a, Result<Submission, AocError>>;
+}
@@ -0,0 +1,177 @@
+use clap::{Arg, ArgMatches};
+use serde::Serialize;
+
+use crate::{error::AocError, supervise::Usage};
+
+/// Output format of `run` and `tally`, set with `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// A single array with a record per day, printed when all days are done
+    Json,
+    /// One record per line, printed as soon as the day is done
+    Ndjson,
+}
+
+impl Format {
+    pub fn from_matches(matches: &ArgMatches) -> Format {
+        match matches.get_one::<String>("format").map(String::as_str) {
+            Some("json") => Format::Json,
+            Some("ndjson") => Format::Ndjson,
+            _ => Format::Text,
+        }
+    }
+}
+
+pub fn format_argument() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_parser(["text", "json", "ndjson"])
+        .default_value("text")
+        .help("Print the results as text, json or ndjson")
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartRecord {
+    pub part: u8,
+    pub answer: Option<String>,
+    /// The answer submitted to the provider, if known
+    pub expected: Option<String>,
+    /// Only set when there is an expected answer to compare against
+    pub correct: Option<bool>,
+    /// Time printed by the solution, in `time_unit`
+    pub time: Option<usize>,
+}
+
+impl PartRecord {
+    pub fn new(
+        part: u8,
+        answer: Option<String>,
+        expected: Option<String>,
+        time: Option<usize>,
+    ) -> Self {
+        let correct = expected.as_ref().map(|e| answer.as_ref() == Some(e));
+        PartRecord {
+            part,
+            answer,
+            expected,
+            correct,
+            time,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageRecord {
+    pub wall_secs: f64,
+    pub user_secs: Option<f64>,
+    pub sys_secs: Option<f64>,
+    /// Peak resident memory in bytes
+    pub max_rss: Option<u64>,
+}
+
+impl From<Usage> for UsageRecord {
+    fn from(usage: Usage) -> Self {
+        UsageRecord {
+            wall_secs: usage.wall.as_secs_f64(),
+            user_secs: usage.user.map(|d| d.as_secs_f64()),
+            sys_secs: usage.sys.map(|d| d.as_secs_f64()),
+            max_rss: usage.max_rss,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorRecord {
+    /// One of the `ErrorTypes` of tally in snake case, e.g `runtime` or
+    /// `timeout`, or `error` for errors in cargo-aoc itself
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmissionRecord {
+    pub part: u8,
+    pub accepted: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayRecord {
+    pub day: u32,
+    pub title: Option<String>,
+    /// Extension of the implementation that ran
+    pub lang: Option<String>,
+    pub parts: Vec<PartRecord>,
+    pub time_unit: String,
+    pub usage: Option<UsageRecord>,
+    pub exit_status: Option<i32>,
+    pub error: Option<ErrorRecord>,
+    pub submission: Option<SubmissionRecord>,
+}
+
+impl DayRecord {
+    pub fn new(day: u32) -> Self {
+        DayRecord {
+            day,
+            title: None,
+            lang: None,
+            parts: Vec::new(),
+            time_unit: crate::util::get_time_symbol(),
+            usage: None,
+            exit_status: None,
+            error: None,
+            submission: None,
+        }
+    }
+
+    pub fn failed(day: u32, err: &AocError) -> Self {
+        let (kind, exit_status) = match err {
+            AocError::SolutionFailed(status) => ("runtime", status.code()),
+            AocError::Timeout(_) => ("timeout", None),
+            AocError::OutOfMemory(_) => ("out_of_memory", None),
+            _ => ("error", None),
+        };
+
+        DayRecord {
+            exit_status,
+            error: Some(ErrorRecord {
+                kind: kind.to_owned(),
+                message: err.to_string(),
+            }),
+            ..DayRecord::new(day)
+        }
+    }
+}
+
+/// Prints the records in the given format. Does nothing for `Format::Text`,
+/// which is printed as the days run
+pub struct Reporter {
+    format: Format,
+    records: Vec<DayRecord>,
+}
+
+impl Reporter {
+    pub fn new(format: Format) -> Self {
+        Reporter {
+            format,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, record: DayRecord) -> Result<(), AocError> {
+        match self.format {
+            Format::Text => {}
+            Format::Json => self.records.push(record),
+            Format::Ndjson => println!("{}", serde_json::to_string(&record)?),
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), AocError> {
+        if self.format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(&self.records)?);
+        }
+        Ok(())
+    }
+}
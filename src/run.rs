@@ -1,73 +1,416 @@
-use std::io::{BufRead, BufReader};
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    process::ExitCode,
+};
 
-use chrono::prelude::*;
 use clap::ArgMatches;
 
-#[cfg(feature = "submit")]
-use crate::util::submit::{self, get_submit_task};
 use crate::{
-    assert::assert_answer,
+    assert::{passed, print_comparison},
     error::AocError,
-    language::REGISTER,
+    language::{Mode, Register, RunningArgs, Tool},
+    provider::{download_inputs, get_provider, input_path},
+    report::{DayRecord, Format, PartRecord, Reporter},
+    supervise::{Child, Limits, Usage, Watchdog, get_limits},
     util::{
+        Task,
         file::{
-            day_path, download_input_file, get_parse_config, get_root_path, get_running_args,
+            day_path, get_day_args, get_parse_config, get_root_path, get_running_args,
             get_year_from_path,
         },
-        get_day,
+        get_day_title_and_answers, get_days, get_lang, get_part, select_part,
     },
 };
+#[cfg(feature = "submit")]
+use crate::{
+    report::SubmissionRecord,
+    util::submit::{self, get_submit_task},
+};
 
-pub async fn run(matches: &ArgMatches) -> Result<(), AocError> {
-    let day = get_day(matches)?;
-    let path = get_root_path()?;
-    let year = get_year_from_path(&path)?;
+pub async fn ensure_input(day: u32, year: i32, dir: &Path) -> Result<(), AocError> {
+    if !input_path(dir, None).exists() {
+        let provider = get_provider()?;
+        provider.calendar(year)?;
+        download_inputs(provider, year, day, dir).await?;
+    }
+    Ok(())
+}
 
-    let dir = day_path(&path, day).await?;
+/// Starts the solution with the runner registered for its main file
+pub fn start(args: RunningArgs) -> Result<Child, AocError> {
+    let register = Register::for_day(&args.common.root_folder, &args.common.day_folder)?;
+    let runner = register.by_file(&args.common.day_folder, &args.common.file)?;
+    Ok(Child::spawn(runner.execute(args)?)?)
+}
 
-    if !dir.join("input").exists() {
-        let current_year = Utc::now().year();
-        let current_month = Utc::now().month();
+/// Starts a command of the toolchain of the day, like `test` or `lint`
+pub async fn start_tool(
+    root: &Path,
+    day: u32,
+    tool: Tool,
+    mode: Mode,
+    lang: Option<&str>,
+) -> Result<Child, AocError> {
+    let args = get_day_args(root, day, mode, lang).await?;
+    let register = Register::for_day(root, &args.common.day_folder)?;
+    let runner = register.by_file(&args.common.day_folder, &args.common.file)?;
+    let ext = register.lang(&args.common.day_folder, &args.common.file);
 
-        if year < 2015 || year > current_year {
-            return Err(AocError::InvalidYear);
-        }
-        if year == current_year && current_month < 12 {
-            return Err(AocError::InvalidMonth);
-        }
+    let expr = runner
+        .tool(tool, args)
+        .ok_or(AocError::MissingCommand(tool, ext))??;
+    Ok(Child::spawn(expr)?)
+}
 
-        download_input_file(day, year, &dir).await?;
-    }
+/// Prints the output of the solution, killing it if it exceeds the limits.
+/// The output is printed to stderr when stdout is used for the results
+pub fn print_supervised(
+    child: Child,
+    limits: Limits,
+    format: Format,
+) -> Result<(String, Usage), AocError> {
+    let watchdog = Watchdog::start(child.pid(), limits);
+    let out = print_output(child.output(), format != Format::Text);
+    let (status, usage) = child.wait()?;
 
-    let args = get_running_args(matches).await?;
-    let ext = args.common.file.extension().unwrap().to_str().unwrap();
-    let Some(compiler) = REGISTER.by_extension(ext) else {
-        return Err(AocError::UnsupportedLanguage(ext.to_owned()));
-    };
+    match watchdog.finish() {
+        Some(exceeded) => Err(exceeded.into()),
+        None if !status.success() => Err(AocError::SolutionFailed(status)),
+        None => Ok((out, usage)),
+    }
+}
 
-    let reader = compiler.execute(args)?.stderr_to_stdout().reader()?;
+pub fn print_usage(usage: &Usage) {
+    println!("\x1b[2m({})\x1b[0m", usage);
+}
 
+/// Prints the output of the solution while it is running, and returns it
+pub fn print_output(reader: impl Read, to_stderr: bool) -> String {
     let reader = BufReader::new(reader);
     let mut lines = reader.lines();
 
     let mut out = String::new();
     while let Some(Ok(line)) = lines.next() {
-        println!("{}", line);
+        if to_stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
         out.push_str(&line);
         out.push('\n');
     }
+    out
+}
+
+/// Exit codes of `cargo aoc run`. Errors in cargo-aoc itself exit with 1.
+/// When running several days, the highest code wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Success = 0,
+    Error = 1,
+    /// The solution crashed, exited non-zero or exceeded the limits
+    SolutionFailed = 2,
+    /// `--assert` found an answer that doesn't match the submitted one
+    WrongAnswer = 3,
+    /// The submitted answer was not accepted
+    SubmissionRejected = 4,
+}
 
-    if matches.get_flag("assert") {
-        let parse_file = get_parse_config(&path, &dir);
-        assert_answer(&out, day, year, parse_file).await?;
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
     }
+}
+
+impl Status {
+    fn from_error(err: &AocError) -> Status {
+        match err {
+            AocError::SolutionFailed(_) | AocError::Timeout(_) | AocError::OutOfMemory(_) => {
+                Status::SolutionFailed
+            }
+            _ => Status::Error,
+        }
+    }
+}
+
+/// How running a single day went, for the summary when running several days
+enum Outcome {
+    Answered,
+    NoAnswers,
+    Passed,
+    Mismatch,
+    /// The implementations in different languages gave different answers
+    Disagree,
+    #[cfg(feature = "submit")]
+    Rejected,
+    Failed(AocError),
+}
+
+impl Outcome {
+    fn status(&self) -> Status {
+        match self {
+            Outcome::Answered | Outcome::NoAnswers | Outcome::Passed => Status::Success,
+            Outcome::Mismatch | Outcome::Disagree => Status::WrongAnswer,
+            #[cfg(feature = "submit")]
+            Outcome::Rejected => Status::SubmissionRejected,
+            Outcome::Failed(err) => Status::from_error(err),
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Answered => write!(f, "answered"),
+            Outcome::NoAnswers => write!(f, "\x1b[0;33mno answers\x1b[0m"),
+            Outcome::Passed => write!(f, "\x1b[0;32mok\x1b[0m"),
+            Outcome::Mismatch => write!(f, "\x1b[0;31mFAILED\x1b[0m"),
+            Outcome::Disagree => write!(f, "\x1b[0;31manswers differ between languages\x1b[0m"),
+            #[cfg(feature = "submit")]
+            Outcome::Rejected => write!(f, "\x1b[0;31mwrong answer\x1b[0m"),
+            Outcome::Failed(err) => write!(f, "\x1b[0;31merror\x1b[0m: {}", err),
+        }
+    }
+}
+
+/// Runs the implementation of the day in `lang`, or its only one
+async fn run_lang(
+    matches: &ArgMatches,
+    root: &Path,
+    year: i32,
+    day: u32,
+    lang: Option<&str>,
+    limits: Limits,
+    format: Format,
+) -> Result<(Outcome, DayRecord), AocError> {
+    let dir = day_path(root, day).await?;
+    let mut args = get_running_args(matches, day, lang).await?;
+    if args.common.input_file == input_path(&dir, args.part.map(Task::number)) {
+        ensure_input(day, year, &dir).await?;
+        args.common.input_file = input_path(&dir, args.part.map(Task::number));
+    }
+    let lang = Register::for_day(root, &dir)?.lang(&dir, &args.common.file);
+
+    let (out, usage) = print_supervised(start(args)?, limits, format)?;
+    if format == Format::Text {
+        print_usage(&usage);
+    }
+
+    let part = get_part(matches);
+    let parse_file = get_parse_config(root, &dir)?;
+    let (p1, p2) = select_part(parse_file.get_answers(&out), part);
+    let (t1, t2) = select_part(parse_file.get_times(&out), part);
+
+    let mut record = DayRecord::new(day);
+    record.lang = Some(lang);
+    record.usage = Some(usage.into());
+    record.exit_status = Some(0);
 
+    let outcome = if matches.get_flag("assert") {
+        let info = get_day_title_and_answers(day, year as u32).await?;
+        record.title = Some(info.title);
+        record.parts = vec![
+            PartRecord::new(1, p1.clone(), info.part1_answer, t1),
+            PartRecord::new(2, p2.clone(), info.part2_answer, t2),
+        ];
+
+        let last_day = day == get_provider()?.days(year);
+        // Printing the comparison would mix it with the JSON
+        if format == Format::Text {
+            print_comparison(&record.parts, part, last_day);
+        }
+        if passed(&record.parts, part, last_day) {
+            Outcome::Passed
+        } else {
+            Outcome::Mismatch
+        }
+    } else {
+        record.parts = vec![
+            PartRecord::new(1, p1.clone(), None, t1),
+            PartRecord::new(2, p2.clone(), None, t2),
+        ];
+        match (&p1, &p2) {
+            (None, None) => Outcome::NoAnswers,
+            _ => Outcome::Answered,
+        }
+    };
+
+    #[cfg(feature = "submit")]
+    let mut outcome = outcome;
     // Only try to submit if the submit flag is passed
     #[cfg(feature = "submit")]
     if let Some(task) = get_submit_task(matches).transpose()? {
-        let parse_file = get_parse_config(&path, &dir);
-        let output = submit::submit(&out, task, day, year, parse_file).await?;
-        println!("Task {}: {}", task, output);
+        let submission = submit::submit((p1, p2), task, day, year).await?;
+        if format == Format::Text {
+            println!("Task {}: {}", task, submission.message);
+        }
+        if !submission.accepted {
+            outcome = Outcome::Rejected;
+        }
+        record.submission = Some(SubmissionRecord {
+            part: task.number(),
+            accepted: submission.accepted,
+            message: submission.message,
+        });
+    }
+
+    Ok((outcome, record))
+}
+
+/// Runs the day in the language given with `--lang`, or in every language
+/// with `--all-langs`, comparing their answers
+async fn run_day(
+    matches: &ArgMatches,
+    root: &Path,
+    year: i32,
+    day: u32,
+    limits: Limits,
+    format: Format,
+) -> Result<(Outcome, Vec<DayRecord>), AocError> {
+    if !matches.get_flag("all-langs") {
+        let lang = get_lang(matches);
+        let (outcome, record) = run_lang(matches, root, year, day, lang, limits, format).await?;
+        return Ok((outcome, vec![record]));
+    }
+
+    let dir = day_path(root, day).await?;
+    let register = Register::for_day(root, &dir)?;
+    let langs = register
+        .mains(&dir)
+        .into_iter()
+        .map(|main| main.lang)
+        .collect::<Vec<_>>();
+    if langs.is_empty() {
+        return Err(AocError::MissingMain(dir));
+    }
+
+    let mut outcomes = Vec::new();
+    let mut records = Vec::new();
+    for lang in langs {
+        if format == Format::Text {
+            println!("\x1b[1m-- {} --\x1b[0m", lang);
+        }
+        let (outcome, mut record) =
+            match run_lang(matches, root, year, day, Some(&lang), limits, format).await {
+                Ok(res) => res,
+                Err(err) => {
+                    if format == Format::Text {
+                        println!("{}", err);
+                    }
+                    let record = DayRecord::failed(day, &err);
+                    (Outcome::Failed(err), record)
+                }
+            };
+        record.lang = Some(lang);
+        outcomes.push(outcome);
+        records.push(record);
+    }
+
+    let answers = records
+        .iter()
+        .filter(|record| record.error.is_none())
+        .map(|record| {
+            let answers = record.parts.iter().map(|part| part.answer.as_deref());
+            (
+                record.lang.as_deref().unwrap_or_default(),
+                answers.collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let agree = answers.windows(2).all(|pair| pair[0].1 == pair[1].1);
+
+    if format == Format::Text && !agree {
+        println!("\x1b[0;31mAnswers differ between languages\x1b[0m");
+        for (lang, answers) in &answers {
+            let answers = answers.iter().map(|answer| answer.unwrap_or("NA"));
+            println!("  {}: {}", lang, answers.collect::<Vec<_>>().join(", "));
+        }
+    }
+
+    let outcome = match outcomes.into_iter().max_by_key(Outcome::status) {
+        Some(outcome) if agree || outcome.status() > Status::WrongAnswer => outcome,
+        _ => Outcome::Disagree,
+    };
+    Ok((outcome, records))
+}
+
+async fn run_days(
+    matches: &ArgMatches,
+    root: &Path,
+    year: i32,
+    days: &[u32],
+    limits: Limits,
+    format: Format,
+) -> Result<Status, AocError> {
+    let mut reporter = Reporter::new(format);
+    let mut outcomes = Vec::new();
+    for &day in days {
+        if format == Format::Text {
+            println!("\n\x1b[1m--- Day {:02} ---\x1b[0m", day);
+        }
+
+        let (outcome, records) = match run_day(matches, root, year, day, limits, format).await {
+            Ok(res) => res,
+            Err(err) => {
+                if format == Format::Text {
+                    println!("{}", err);
+                }
+                let record = DayRecord::failed(day, &err);
+                (Outcome::Failed(err), vec![record])
+            }
+        };
+        for record in records {
+            reporter.push(record)?;
+        }
+        outcomes.push((day, outcome));
+    }
+    reporter.finish()?;
+
+    if format == Format::Text {
+        println!("\n\x1b[1m--- Summary ---\x1b[0m");
+        for (day, outcome) in &outcomes {
+            println!("Day {:02}: {}", day, outcome);
+        }
+    }
+
+    Ok(outcomes
+        .iter()
+        .map(|(_, outcome)| outcome.status())
+        .max()
+        .unwrap_or(Status::Success))
+}
+
+pub async fn run(matches: &ArgMatches) -> Result<Status, AocError> {
+    #[cfg(feature = "watch")]
+    if matches.get_flag("watch") {
+        return crate::watch::watch(matches).await.map(|_| Status::Success);
+    }
+
+    let path = get_root_path()?;
+    let year = get_year_from_path(&path)?;
+    let days = get_days(matches, year)?;
+    let limits = get_limits(matches)?;
+    let format = Format::from_matches(matches);
+
+    match days.as_slice() {
+        [day] if format == Format::Text => {
+            match run_day(matches, &path, year, *day, limits, format).await {
+                Ok((outcome, _)) => Ok(outcome.status()),
+                // The solution failing is not an error in cargo-aoc
+                Err(err) if Status::from_error(&err) == Status::SolutionFailed => {
+                    eprintln!("{}", err);
+                    Ok(Status::SolutionFailed)
+                }
+                Err(err) => Err(err),
+            }
+        }
+        _ => {
+            #[cfg(feature = "submit")]
+            if days.len() > 1 && matches.contains_id("submit") {
+                return Err(AocError::SubmitMultipleDays);
+            }
+            run_days(matches, &path, year, &days, limits, format).await
+        }
     }
-    Ok(())
 }
@@ -2,7 +2,10 @@ use std::path::Path;
 
 use clap::ArgMatches;
 
-use crate::error::AocError;
+use crate::{
+    error::AocError,
+    util::user_config::{self, UserConfig},
+};
 
 async fn setup_template_project(year: i32) -> Result<(), AocError> {
     if Path::new(&format!("{year}")).exists() {
@@ -48,6 +51,25 @@ async fn get_session_token() -> Result<(), AocError> {
     Ok(())
 }
 
+fn get_contact() -> Result<(), AocError> {
+    if user_config::get_contact()?.is_none() {
+        println!(
+            "Enter contact info (e.g. email or github username) to identify your requests to \
+             adventofcode.com"
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+
+        if !input.is_empty() {
+            let mut config = UserConfig::read()?;
+            config.contact = Some(input.to_owned());
+            config.save()?;
+        }
+    }
+    Ok(())
+}
+
 fn get_year(matches: &ArgMatches) -> Result<i32, AocError> {
     let year = matches
         .get_one::<String>("year")
@@ -64,5 +86,6 @@ pub async fn setup(args: &ArgMatches) -> Result<(), AocError> {
 
     setup_template_project(year).await?;
     get_session_token().await?;
+    get_contact()?;
     Ok(())
 }
@@ -0,0 +1,481 @@
+use std::{
+    io::PipeReader,
+    process::ExitStatus,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use clap::{Arg, ArgMatches};
+use duct::{Expression, Handle};
+
+use crate::{error::AocError, util::user_config::UserConfig};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Process groups started by `start_group` that have not been waited on.
+/// They don't get the SIGINT of the terminal, so Ctrl-C is forwarded to them
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Limits for a solution. Set with `--timeout` and `--max-memory`, or
+/// `timeout` and `max_memory` in the global config
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    pub max_memory: Option<u64>,
+}
+
+impl Limits {
+    fn is_empty(&self) -> bool {
+        self.timeout.is_none() && self.max_memory.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Exceeded {
+    Timeout(Duration),
+    OutOfMemory(u64),
+}
+
+impl From<Exceeded> for AocError {
+    fn from(exceeded: Exceeded) -> Self {
+        match exceeded {
+            Exceeded::Timeout(timeout) => AocError::Timeout(timeout),
+            Exceeded::OutOfMemory(max) => AocError::OutOfMemory(max),
+        }
+    }
+}
+
+/// Parses durations like `500ms`, `30s`, `2m` or `30`, which is seconds
+pub fn parse_duration(s: &str) -> Result<Duration, AocError> {
+    let s = s.trim();
+    let (num, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let num = num.parse::<u64>()?;
+
+    match unit.trim() {
+        "ms" => Ok(Duration::from_millis(num)),
+        "" | "s" => Ok(Duration::from_secs(num)),
+        "m" => Ok(Duration::from_secs(num * 60)),
+        _ => Err(AocError::LimitError(format!("invalid duration `{}`", s))),
+    }
+}
+
+/// Parses sizes like `512M`, `2G` or `1048576`, which is bytes
+pub fn parse_memory(s: &str) -> Result<u64, AocError> {
+    let s = s.trim();
+    let (num, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let num = num.parse::<u64>()?;
+
+    let factor = match unit.trim().to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return Err(AocError::LimitError(format!("invalid memory size `{}`", s))),
+    };
+    Ok(num * factor)
+}
+
+pub fn limit_arguments() -> [Arg; 2] {
+    [
+        Arg::new("timeout")
+            .long("timeout")
+            .required(false)
+            .help("Kill the solution after the given time, e.g 500ms, 30s or 2m"),
+        Arg::new("max-memory")
+            .long("max-memory")
+            .required(false)
+            .help("Kill the solution if it uses more memory than given, e.g 512M or 2G"),
+    ]
+}
+
+pub fn format_memory(bytes: u64) -> String {
+    format!("{}MiB", bytes >> 20)
+}
+
+pub fn get_limits(matches: &ArgMatches) -> Result<Limits, AocError> {
+    let config = UserConfig::load()?;
+
+    let timeout = matches
+        .get_one::<String>("timeout")
+        .or(config.timeout.as_ref())
+        .map(|s| parse_duration(s))
+        .transpose()?;
+    let max_memory = matches
+        .get_one::<String>("max-memory")
+        .or(config.max_memory.as_ref())
+        .map(|s| parse_memory(s))
+        .transpose()?;
+    if max_memory.is_some() && !cfg!(target_os = "linux") {
+        return Err(AocError::LimitError(
+            "--max-memory is only supported on Linux".to_owned(),
+        ));
+    }
+
+    Ok(Limits {
+        timeout,
+        max_memory,
+    })
+}
+
+/// Runs the expression in its own process group, so that everything it
+/// spawns can be killed together
+pub fn process_group(expr: Expression) -> Expression {
+    #[cfg(unix)]
+    {
+        expr.before_spawn(|cmd| {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+            Ok(())
+        })
+    }
+    #[cfg(not(unix))]
+    {
+        expr
+    }
+}
+
+/// Starts the expression in its own process group, which is interrupted on
+/// Ctrl-C until [`wait_with_usage`] has waited for it
+pub fn start_group(expr: Expression) -> std::io::Result<Handle> {
+    let handle = process_group(expr).start()?;
+    if let Some(pid) = handle.pids().first() {
+        RUNNING.lock().unwrap().push(*pid);
+    }
+    Ok(handle)
+}
+
+/// Forwards Ctrl-C to the running process groups and exits, as they would
+/// otherwise be left running
+pub fn forward_ctrl_c() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        for pid in RUNNING.lock().unwrap().drain(..) {
+            interrupt_group(pid);
+        }
+        crate::util::file::remove_temp_inputs();
+        std::process::exit(130);
+    });
+}
+
+fn interrupt_group(pid: u32) {
+    #[cfg(unix)]
+    // SAFETY: Sending a signal has no memory safety requirements
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGINT);
+    }
+    #[cfg(not(unix))]
+    kill_group(pid);
+}
+
+pub fn kill_group(pid: u32) {
+    #[cfg(unix)]
+    // SAFETY: Sending a signal has no memory safety requirements. The
+    // negative pid targets the process group started by `process_group`
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    {
+        let pid = pid.to_string();
+        let _ = duct::cmd!("taskkill", "/F", "/T", "/PID", pid)
+            .stdout_null()
+            .stderr_null()
+            .run();
+    }
+}
+
+/// Resident memory of all processes in the process group
+#[cfg(target_os = "linux")]
+fn group_memory(pgid: u32) -> u64 {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|s| s.parse::<u32>().is_ok())
+        })
+        .filter_map(|entry| {
+            // The process group is the fifth field, after the name in parentheses
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            let (_, rest) = stat.rsplit_once(')')?;
+            let pgrp = rest.split_whitespace().nth(2)?.parse::<u32>().ok()?;
+            if pgrp != pgid {
+                return None;
+            }
+
+            let status = std::fs::read_to_string(entry.path().join("status")).ok()?;
+            let rss = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+            let kb = rss.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+            Some(kb * 1024)
+        })
+        .sum()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn group_memory(_pgid: u32) -> u64 {
+    0
+}
+
+/// Resources used by a solution, measured by us rather than the solution
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Usage {
+    pub wall: Duration,
+    pub user: Option<Duration>,
+    pub sys: Option<Duration>,
+    /// Peak resident memory in bytes
+    pub max_rss: Option<u64>,
+}
+
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "wall {:.2?}", self.wall)?;
+        if let (Some(user), Some(sys)) = (self.user, self.sys) {
+            write!(f, ", user {:.2?}, sys {:.2?}", user, sys)?;
+        }
+        if let Some(max_rss) = self.max_rss {
+            write!(f, ", peak memory {}", format_memory(max_rss))?;
+        }
+        Ok(())
+    }
+}
+
+/// Waits for the process and measures it with the rusage from `wait4`. This
+/// reaps the process, so the handle must not be waited on afterwards
+#[cfg(unix)]
+pub fn wait_with_usage(handle: Handle, started: Instant) -> std::io::Result<(ExitStatus, Usage)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let Some(pid) = handle.pids().first().copied() else {
+        return Err(std::io::Error::other("process has no pid"));
+    };
+
+    let mut status = 0;
+    // SAFETY: All-zero is a valid `rusage`
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: The pointers are valid for the duration of the call
+    let res = unsafe { libc::wait4(pid as libc::pid_t, &mut status, 0, &mut rusage) };
+    let wall = started.elapsed();
+    RUNNING.lock().unwrap().retain(|running| *running != pid);
+    if res < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let timeval = |tv: libc::timeval| {
+        Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+    };
+    // Linux reports kilobytes, macOS reports bytes
+    let max_rss = if cfg!(target_os = "macos") {
+        rusage.ru_maxrss as u64
+    } else {
+        rusage.ru_maxrss as u64 * 1024
+    };
+
+    let usage = Usage {
+        wall,
+        user: Some(timeval(rusage.ru_utime)),
+        sys: Some(timeval(rusage.ru_stime)),
+        max_rss: Some(max_rss),
+    };
+    Ok((ExitStatus::from_raw(status), usage))
+}
+
+#[cfg(not(unix))]
+pub fn wait_with_usage(handle: Handle, started: Instant) -> std::io::Result<(ExitStatus, Usage)> {
+    let status = handle.wait()?.status;
+    let usage = Usage {
+        wall: started.elapsed(),
+        ..Default::default()
+    };
+    Ok((status, usage))
+}
+
+/// A running solution with stdout and stderr combined into `output`
+pub struct Child {
+    handle: Handle,
+    output: PipeReader,
+    started: Instant,
+}
+
+impl Child {
+    pub fn spawn(expr: Expression) -> std::io::Result<Child> {
+        let (output, writer) = std::io::pipe()?;
+        let started = Instant::now();
+        let handle = start_group(expr.stderr_to_stdout().stdout_file(writer).unchecked())?;
+
+        Ok(Child {
+            handle,
+            output,
+            started,
+        })
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.handle.pids().first().copied()
+    }
+
+    pub fn output(&self) -> &PipeReader {
+        &self.output
+    }
+
+    pub fn wait(self) -> std::io::Result<(ExitStatus, Usage)> {
+        wait_with_usage(self.handle, self.started)
+    }
+}
+
+/// Kills the process group of `pid` if it exceeds the limits
+pub struct Watchdog {
+    finished: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Option<Exceeded>>>,
+}
+
+impl Watchdog {
+    pub fn start(pid: Option<u32>, limits: Limits) -> Self {
+        let finished = Arc::new(AtomicBool::new(false));
+        let Some(pid) = pid.filter(|_| !limits.is_empty()) else {
+            return Watchdog {
+                finished,
+                handle: None,
+            };
+        };
+
+        let handle = std::thread::spawn({
+            let finished = finished.clone();
+            move || {
+                let start = Instant::now();
+                while !finished.load(Ordering::Relaxed) {
+                    let exceeded = match limits {
+                        Limits {
+                            timeout: Some(timeout),
+                            ..
+                        } if start.elapsed() > timeout => Some(Exceeded::Timeout(timeout)),
+                        Limits {
+                            max_memory: Some(max),
+                            ..
+                        } if group_memory(pid) > max => Some(Exceeded::OutOfMemory(max)),
+                        _ => None,
+                    };
+
+                    if exceeded.is_some() {
+                        kill_group(pid);
+                        return exceeded;
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+                None
+            }
+        });
+
+        Watchdog {
+            finished,
+            handle: Some(handle),
+        }
+    }
+
+    /// Call when the process has exited. Returns the limit that was exceeded,
+    /// if any
+    pub fn finish(self) -> Option<Exceeded> {
+        self.finished.store(true, Ordering::Relaxed);
+        self.handle.and_then(|h| h.join().ok().flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[cfg(unix)]
+    use super::{Child, Limits, RUNNING, Watchdog};
+    use super::{parse_duration, parse_memory};
+    #[cfg(unix)]
+    use crate::error::AocError;
+
+    /// Runs `script` under the watchdog, returning its output and the limit
+    /// it exceeded
+    #[cfg(unix)]
+    fn supervised(script: &str, limits: Limits) -> (String, Option<AocError>) {
+        use std::io::Read;
+
+        let child = Child::spawn(duct::cmd!("sh", "-c", script)).unwrap();
+        let watchdog = Watchdog::start(child.pid(), limits);
+        let mut output = child.output().try_clone().unwrap();
+        child.wait().unwrap();
+        let exceeded = watchdog.finish().map(AocError::from);
+
+        // Only the process group could still hold the pipe open
+        let mut s = String::new();
+        output.read_to_string(&mut s).unwrap();
+        (s, exceeded)
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert!(parse_duration("2h").is_err());
+    }
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("1024").unwrap(), 1024);
+        assert_eq!(parse_memory("512M").unwrap(), 512 << 20);
+        assert_eq!(parse_memory("2GB").unwrap(), 2 << 30);
+        assert!(parse_memory("2T").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_group() {
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        // The background sleep holds the output open until it is killed too
+        let (output, exceeded) = supervised("sleep 30 & echo started; sleep 30", limits);
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(output, "started\n");
+        assert!(matches!(exceeded, Some(AocError::Timeout(_))));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_memory_limit() {
+        let limits = Limits {
+            max_memory: Some(16 << 20),
+            ..Default::default()
+        };
+        let script = "x=$(head -c 100000000 /dev/zero | tr '\\0' a); sleep 30";
+        let (_, exceeded) = supervised(script, limits);
+
+        assert!(matches!(exceeded, Some(AocError::OutOfMemory(max)) if max == 16 << 20));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_usage() {
+        let script = "i=0; while [ $i -lt 20000 ]; do i=$((i + 1)); done; echo $i";
+        let child = Child::spawn(duct::cmd!("sh", "-c", script)).unwrap();
+        let pid = child.pid().unwrap();
+        assert!(RUNNING.lock().unwrap().contains(&pid));
+
+        let (status, usage) = child.wait().unwrap();
+        assert!(status.success());
+        assert!(!RUNNING.lock().unwrap().contains(&pid));
+        assert!(usage.wall > Duration::ZERO);
+        assert!(usage.user.unwrap() + usage.sys.unwrap() > Duration::ZERO);
+        assert!(usage.max_rss.unwrap() > 0);
+    }
+}
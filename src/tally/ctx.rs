@@ -17,16 +17,23 @@ use crate::{
 pub struct PipelineCtx {
     pub year: usize,
     pub root: PathBuf,
+    /// Only tally the implementations in this language
+    pub lang: Option<String>,
     pub configs: HashMap<usize, Config>,
     pub infos: HashMap<usize, AocInfo>,
     pub errors: Vec<DayError>,
 }
 
 impl PipelineCtx {
-    pub async fn new(year: usize, root: PathBuf, days: &[usize]) -> Result<Self, AocError> {
+    pub async fn new(
+        year: usize,
+        root: PathBuf,
+        days: &[usize],
+        lang: Option<String>,
+    ) -> Result<Self, AocError> {
         let infos = get_aoc_infos(year, days).await?;
         let discovered = get_discovered_days(&root, days)?;
-        let configs = get_aoc_configs(&root, &discovered);
+        let configs = get_aoc_configs(&root, &discovered)?;
 
         let errors = {
             let mut errors = Vec::new();
@@ -48,6 +55,7 @@ impl PipelineCtx {
         Ok(Self {
             year,
             root,
+            lang,
             configs,
             infos,
             errors,
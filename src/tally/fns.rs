@@ -4,6 +4,9 @@ use indicatif::MultiProgress;
 
 use crate::{
     error::AocError,
+    language::Register,
+    report::{DayRecord, ErrorRecord, PartRecord},
+    supervise::Limits,
     tally::{
         ctx::PipelineCtx,
         print_fns::BuildRes,
@@ -44,19 +47,26 @@ pub async fn get_compiled_days(
     ctx: &mut PipelineCtx,
     discovered: Vec<DiscoveredDay>,
 ) -> Result<Vec<CompiledDay>, AocError> {
+    let registers = discovered
+        .iter()
+        .map(|d| Register::for_day(&ctx.root, &d.folder))
+        .collect::<Result<Vec<_>, _>>()?;
+
     let progress = get_progressbar(discovered.len() as u64);
     progress.set_message("preparing");
 
-    let prepared = futures::future::join_all(discovered.into_iter().map(|d| {
-        let progress = progress.clone();
-        let ctx = &*ctx;
-        async move {
-            let args = prepare_args(ctx, &d.folder, d.day).await;
-            progress.inc(1);
-            args.map(|args| (d.day, args)).ok_or(d.day)
-        }
-    }))
-    .await;
+    let prepared =
+        futures::future::join_all(discovered.into_iter().zip(&registers).map(|(d, register)| {
+            let progress = progress.clone();
+            let ctx = &*ctx;
+            async move {
+                let args = prepare_args(ctx, register, &d.folder, d.day).await;
+                progress.inc(1);
+                args.map(|args| (d.day, args, register))
+                    .map_err(|err| (d.day, err))
+            }
+        }))
+        .await;
 
     progress.finish_and_clear();
 
@@ -68,11 +78,11 @@ pub async fn get_compiled_days(
         let progress = &progress;
         for res in prepared.into_iter() {
             match res {
-                Ok((day, args)) => {
-                    handles.push(scope.spawn(move || compile_day(day, args, progress)));
+                Ok((day, args, register)) => {
+                    handles.push(scope.spawn(move || compile_day(day, args, register, progress)));
                 }
-                Err(day) => {
-                    ctx.push_error(day, ErrorTypes::InputDownload);
+                Err((day, err)) => {
+                    ctx.push_error(day, err);
                 }
             }
         }
@@ -108,6 +118,7 @@ pub async fn get_verified_days(
             Some(info) if !info.is_unimplemented() => {
                 out.push(VerifiedDay {
                     day: c.day,
+                    lang: c.lang,
                     expr: c.expr,
                     info: info.clone(),
                 });
@@ -124,6 +135,7 @@ pub async fn get_run_result(
     ctx: &mut PipelineCtx,
     days: Vec<VerifiedDay>,
     num_runs: usize,
+    limits: Limits,
 ) -> Vec<RunDayResult> {
     let multi = MultiProgress::new();
 
@@ -136,9 +148,10 @@ pub async fn get_run_result(
             pb.set_message(format!("Running day {}", day.day));
             let config = ctx.configs[&day.day].clone();
             handles.push(scope.spawn(move || {
-                run_day(num_runs, day.expr, &config, &pb)
+                run_day(num_runs, day.expr, &config, limits, &pb)
                     .map(|run_res| RunDayResult {
                         day: day.day,
+                        lang: day.lang,
                         info: day.info.clone(),
                         run: run_res,
                     })
@@ -162,6 +175,39 @@ pub async fn get_run_result(
     results
 }
 
+pub fn convert_to_records(ctx: PipelineCtx, days: Vec<RunDayResult>) -> Vec<DayRecord> {
+    let mut records = days
+        .into_iter()
+        .map(|res| DayRecord {
+            title: Some(res.info.title),
+            lang: Some(res.lang),
+            parts: vec![
+                PartRecord::new(1, res.run.p1.value, res.info.part1_answer, res.run.p1.time),
+                PartRecord::new(2, res.run.p2.value, res.info.part2_answer, res.run.p2.time),
+            ],
+            usage: Some(res.run.usage.into()),
+            exit_status: Some(0),
+            ..DayRecord::new(res.day as u32)
+        })
+        .chain(ctx.errors.into_iter().map(|e| DayRecord {
+            title: Some(e.info.title),
+            lang: ctx.lang.clone(),
+            exit_status: match e.error {
+                ErrorTypes::Runtime(_, code) => code,
+                _ => None,
+            },
+            error: Some(ErrorRecord {
+                kind: e.error.kind().to_owned(),
+                message: e.error.to_string(),
+            }),
+            ..DayRecord::new(e.day as u32)
+        }))
+        .collect::<Vec<_>>();
+
+    records.sort_unstable_by_key(|r| r.day);
+    records
+}
+
 pub fn convert_to_print_format(
     ctx: PipelineCtx,
     days: Vec<RunDayResult>,
@@ -1,19 +1,25 @@
+use std::path::Path;
+
 use clap::ArgMatches;
 
 use crate::{
     error::AocError,
+    provider::get_provider,
+    report::{Format, Reporter},
+    supervise::{Limits, get_limits},
     tally::{
         ctx::PipelineCtx,
         fns::{
-            convert_to_print_format, get_compiled_days, get_discovered_days, get_run_result,
-            get_verified_days,
+            convert_to_print_format, convert_to_records, get_compiled_days, get_discovered_days,
+            get_run_result, get_verified_days,
         },
-        print_fns::print_table,
-        util::{get_number_of_runs, get_possible_days},
+        print_fns::{print_compare_table, print_table},
+        types::RunDayResult,
+        util::{get_langs, get_number_of_runs},
     },
     util::{
         file::{get_root_path, get_year_from_path},
-        verify_token,
+        get_lang,
     },
 };
 
@@ -23,25 +29,83 @@ mod print_fns;
 mod types;
 mod util;
 
+/// Runs the pipeline for the implementations in `lang`, or the only one of
+/// each day
+async fn tally_lang(
+    year: usize,
+    root: &Path,
+    days: &[usize],
+    lang: Option<String>,
+    number_of_runs: usize,
+    limits: Limits,
+) -> Result<(PipelineCtx, Vec<RunDayResult>), AocError> {
+    let mut ctx = PipelineCtx::new(year, root.to_path_buf(), days, lang).await?;
+
+    let discovered = get_discovered_days(root, days)?;
+    let compiled = get_compiled_days(&mut ctx, discovered).await?;
+    let verified = get_verified_days(&mut ctx, compiled).await?;
+    let res = get_run_result(&mut ctx, verified, number_of_runs, limits).await;
+
+    Ok((ctx, res))
+}
+
 pub async fn tally(matches: &ArgMatches) -> Result<(), AocError> {
-    verify_token().await?;
+    let provider = get_provider()?;
+    provider.verify_token().await?;
 
     let number_of_runs = get_number_of_runs(matches)?;
+    let limits = get_limits(matches)?;
 
     let root = get_root_path()?;
     let year = get_year_from_path(&root)? as usize;
-    let days = get_possible_days(year)?;
-
-    let mut ctx = PipelineCtx::new(year, root.clone(), &days).await?;
+    let days = provider
+        .calendar(year as i32)?
+        .into_iter()
+        .map(|day| day as usize)
+        .collect::<Vec<_>>();
 
-    let discovered = get_discovered_days(&root, &days)?;
-    let compiled = get_compiled_days(&mut ctx, discovered).await?;
-    let verified = get_verified_days(&mut ctx, compiled).await?;
-    let res = get_run_result(&mut ctx, verified, number_of_runs).await;
+    let langs = if matches.get_flag("compare-langs") {
+        get_langs(&root, &get_discovered_days(&root, &days)?)?
+            .into_iter()
+            .map(Some)
+            .collect()
+    } else {
+        vec![get_lang(matches).map(str::to_owned)]
+    };
 
-    let converted = convert_to_print_format(ctx, res);
+    let mut results = Vec::new();
+    for lang in langs {
+        results.push(tally_lang(year, &root, &days, lang, number_of_runs, limits).await?);
+    }
 
-    print_table(converted, year);
+    match Format::from_matches(matches) {
+        Format::Text if matches.get_flag("compare-langs") => {
+            let columns = results
+                .into_iter()
+                .map(|(ctx, res)| {
+                    (
+                        ctx.lang.clone().unwrap_or_default(),
+                        convert_to_print_format(ctx, res),
+                    )
+                })
+                .collect();
+            print_compare_table(columns, year);
+        }
+        Format::Text => {
+            for (ctx, res) in results {
+                print_table(convert_to_print_format(ctx, res), year);
+            }
+        }
+        format => {
+            let mut reporter = Reporter::new(format);
+            for (ctx, res) in results {
+                for record in convert_to_records(ctx, res) {
+                    reporter.push(record)?;
+                }
+            }
+            reporter.finish()?;
+        }
+    }
 
     Ok(())
 }
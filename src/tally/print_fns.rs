@@ -1,8 +1,11 @@
+use std::collections::BTreeMap;
+
 use table_generator::{Column, Table};
 
 use crate::{
+    supervise::{Usage, format_memory},
     tally::{
-        types::{DayError, RunDayResult},
+        types::{DayError, ErrorTypes, RunDayResult},
         util::format_duration,
     },
     util::get_time_symbol,
@@ -26,6 +29,7 @@ pub struct BuildRes {
     pub day: usize,
     pub info: TableInfo,
     pub time: Time,
+    pub usage: Usage,
 }
 
 impl From<RunDayResult> for BuildRes {
@@ -46,6 +50,7 @@ impl From<RunDayResult> for BuildRes {
             day: res.day,
             info: table_info,
             time: Time(res.run.p1.time, res.run.p2.time),
+            usage: res.run.usage,
         }
     }
 }
@@ -72,12 +77,30 @@ fn create_rows(day: &BuildRes) -> (Vec<String>, Vec<String>) {
     )
 }
 
+fn create_process_row(day: &BuildRes) -> Vec<String> {
+    let wall = format!("{:.2?}", day.usage.wall);
+    let memory = day
+        .usage
+        .max_rss
+        .map(format_memory)
+        .unwrap_or("NA".to_string());
+    vec![wall, memory]
+}
+
 pub fn print_table(days: Vec<Result<BuildRes, DayError>>, year: usize) {
     let mut table = Table::new(format!("Advent of Code {year}"));
 
+    // Fall back to what we measured when a solution doesn't print its time
+    let show_process = days
+        .iter()
+        .flatten()
+        .any(|day| day.time.0.is_none() || day.time.1.is_none());
+    let last_col = if show_process { 3 } else { 2 };
+
     let mut day_col = Column::new("Day");
     let mut part1_col = Column::new("Part 1");
     let mut part2_col = Column::new("Part 2");
+    let mut process_col = Column::new("Process");
 
     for (i, day) in days.into_iter().enumerate() {
         match day {
@@ -86,15 +109,17 @@ pub fn print_table(days: Vec<Result<BuildRes, DayError>>, year: usize) {
                 let (p1, p2) = create_rows(&day);
                 part1_col.add_row(p1);
                 part2_col.add_row(p2);
+                process_col.add_row(create_process_row(&day));
             }
             Err(e) => {
                 day_col.add_row(vec![e.day.to_string(), e.info.title.clone()]);
                 let text = e.error.to_string().replace('\n', " ");
-                table.add_span(i, 1..=2, text);
+                table.add_span(i, 1..=last_col, text);
 
                 // Need to add a dummy column. the span will override it.
                 part1_col.add_row(vec!["", "", ""]);
                 part2_col.add_row(vec!["", "", ""]);
+                process_col.add_row(vec!["", ""]);
             }
         }
     }
@@ -102,6 +127,66 @@ pub fn print_table(days: Vec<Result<BuildRes, DayError>>, year: usize) {
     table.add_column(day_col);
     table.add_column(part1_col);
     table.add_column(part2_col);
+    if show_process {
+        table.add_column(process_col);
+    }
+    println!("{}", table);
+}
+
+/// Prints a table with a column per language, with the times of both parts
+/// and the measured wall time of each day
+pub fn print_compare_table(langs: Vec<(String, Vec<Result<BuildRes, DayError>>)>, year: usize) {
+    let mut table = Table::new(format!("Advent of Code {year}"));
+
+    let mut days = BTreeMap::new();
+    for (_, results) in &langs {
+        for res in results {
+            let (day, title) = match res {
+                Ok(day) => (day.day, &day.info.title),
+                Err(e) => (e.day, &e.info.title),
+            };
+            days.entry(day).or_insert_with(|| title.clone());
+        }
+    }
+
+    let mut day_col = Column::new("Day");
+    for (day, title) in &days {
+        day_col.add_row(vec![day.to_string(), title.clone(), String::new()]);
+    }
+    table.add_column(day_col);
+
+    for (lang, results) in langs {
+        let mut col = Column::new(&lang);
+        for day in days.keys() {
+            let res = results.iter().find(|res| match res {
+                Ok(res) => res.day == *day,
+                Err(e) => e.day == *day,
+            });
+            let row = match res {
+                Some(Ok(day)) => {
+                    let (p1, p2) = create_rows(day);
+                    let wall = create_process_row(day).swap_remove(0);
+                    vec![
+                        format!("{} {}", p1[1], p1[2]),
+                        format!("{} {}", p2[1], p2[2]),
+                        wall,
+                    ]
+                }
+                Some(Err(DayError {
+                    error: ErrorTypes::NoMain(_),
+                    ..
+                }))
+                | None => vec!["-".to_owned(), String::new(), String::new()],
+                Some(Err(e)) => {
+                    let text = e.error.to_string().replace('\n', " ");
+                    vec![text, String::new(), String::new()]
+                }
+            };
+            col.add_row(row);
+        }
+        table.add_column(col);
+    }
+
     println!("{}", table);
 }
 
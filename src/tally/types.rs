@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use duct::Expression;
 
-use crate::util::AocInfo;
+use crate::{
+    supervise::{Exceeded, Usage, format_memory},
+    util::AocInfo,
+};
 
 #[derive(Debug)]
 pub struct DiscoveredDay {
@@ -13,12 +16,14 @@ pub struct DiscoveredDay {
 #[derive(Debug)]
 pub struct CompiledDay {
     pub day: usize,
+    pub lang: String,
     pub expr: Expression,
 }
 
 #[derive(Debug)]
 pub struct VerifiedDay {
     pub day: usize,
+    pub lang: String,
     pub expr: Expression,
     pub info: AocInfo,
 }
@@ -26,6 +31,7 @@ pub struct VerifiedDay {
 #[derive(Debug)]
 pub struct RunDayResult {
     pub day: usize,
+    pub lang: String,
     pub info: AocInfo,
     pub run: RunRes,
 }
@@ -42,12 +48,46 @@ pub enum ErrorTypes {
     MissingDay,
     InputDownload,
     Compiler(String),
-    Runtime(String),
+    /// The stderr and exit code of the solution
+    Runtime(String, Option<i32>),
     MissingImplementation,
+    /// No main file, or one in the wrong language
+    NoMain(String),
     MissingExtension,
     GetAnswers,
     Unsupported(String),
     DuctError(std::io::Error),
+    Timeout(std::time::Duration),
+    OutOfMemory(u64),
+}
+
+impl From<Exceeded> for ErrorTypes {
+    fn from(exceeded: Exceeded) -> Self {
+        match exceeded {
+            Exceeded::Timeout(timeout) => ErrorTypes::Timeout(timeout),
+            Exceeded::OutOfMemory(max) => ErrorTypes::OutOfMemory(max),
+        }
+    }
+}
+
+impl ErrorTypes {
+    /// Name of the error in machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MissingDay => "missing_day",
+            Self::InputDownload => "input_download",
+            Self::Compiler(_) => "compiler",
+            Self::Runtime(..) => "runtime",
+            Self::MissingImplementation => "missing_implementation",
+            Self::NoMain(_) => "no_main",
+            Self::MissingExtension => "missing_extension",
+            Self::GetAnswers => "get_answers",
+            Self::Unsupported(_) => "unsupported",
+            Self::DuctError(_) => "command",
+            Self::Timeout(_) => "timeout",
+            Self::OutOfMemory(_) => "out_of_memory",
+        }
+    }
 }
 
 impl std::fmt::Display for ErrorTypes {
@@ -56,12 +96,15 @@ impl std::fmt::Display for ErrorTypes {
             Self::MissingDay => write!(f, "Missing day"),
             Self::InputDownload => write!(f, "Could not download input"),
             Self::Compiler(s) => write!(f, "Compiler error: {}", s),
-            Self::Runtime(s) => write!(f, "Runtime error: {}", s),
+            Self::Runtime(s, _) => write!(f, "Runtime error: {}", s),
             Self::MissingExtension => write!(f, "Missing extension"),
             Self::MissingImplementation => write!(f, "Missing implementation"),
+            Self::NoMain(s) => write!(f, "{}", s),
             Self::GetAnswers => write!(f, "Error getting answers"),
             Self::Unsupported(ext) => write!(f, "Unsuppored lang: {}", ext),
             Self::DuctError(err) => write!(f, "Command err: {}", err),
+            Self::Timeout(timeout) => write!(f, "Timed out after {:?}", timeout),
+            Self::OutOfMemory(max) => write!(f, "Exceeded memory limit of {}", format_memory(*max)),
         }
     }
 }
@@ -75,4 +118,6 @@ pub struct Answer {
 pub struct RunRes {
     pub p1: Answer,
     pub p2: Answer,
+    /// Measured by us, used when the solution doesn't print the time
+    pub usage: Usage,
 }
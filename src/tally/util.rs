@@ -1,8 +1,15 @@
-use std::{collections::HashMap, io::Read, path::Path};
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use crate::{
     error::AocError,
-    language::{Common, REGISTER, RunningArgs},
+    language::{Common, Mode, Register, RunningArgs},
+    provider::{download_inputs, get_provider, input_path},
+    supervise::{Limits, Usage, Watchdog, start_group, wait_with_usage},
     tally::{
         ctx::PipelineCtx,
         types::{Answer, CompiledDay, DiscoveredDay, ErrorTypes, RunRes},
@@ -10,11 +17,10 @@ use crate::{
     task_config::Config,
     util::{
         AocInfo,
-        file::{download_input_file, find_file, get_parse_config},
+        file::{find_main, get_parse_config},
         get_day_title_and_answers, get_time_symbol,
     },
 };
-use chrono::prelude::*;
 use clap::ArgMatches;
 use duct::Expression;
 use futures::future::join_all;
@@ -30,33 +36,28 @@ pub fn get_progressbar(len: u64) -> ProgressBar {
     ProgressBar::new(len).with_style(sty)
 }
 
-pub fn get_possible_days(year: usize) -> Result<Vec<usize>, AocError> {
-    let now = chrono::Utc::now();
-    const LAST_DAY_2025: usize = 12;
-
-    if (2015..=2024).contains(&year) {
-        return Ok((1..=25).collect());
-    }
-
-    if year as i32 == now.year() {
-        if now.month() == 12 {
-            let day = now.day() as usize;
-            Ok((1..=day.min(LAST_DAY_2025)).collect())
-        } else {
-            Err(AocError::InvalidMonth)
-        }
-    } else {
-        Ok((1..=LAST_DAY_2025).collect())
+/// Every language any of the days is implemented in
+pub fn get_langs(root: &Path, days: &[DiscoveredDay]) -> Result<Vec<String>, AocError> {
+    let mut langs = Vec::new();
+    for d in days {
+        let mains = Register::for_day(root, &d.folder)?.compiled_mains(&d.folder);
+        langs.extend(mains.into_iter().map(|main| main.lang));
     }
+    langs.sort();
+    langs.dedup();
+    Ok(langs)
 }
 
-pub fn get_aoc_configs(year: &Path, days: &[DiscoveredDay]) -> HashMap<usize, Config> {
+pub fn get_aoc_configs(
+    year: &Path,
+    days: &[DiscoveredDay],
+) -> Result<HashMap<usize, Config>, AocError> {
     days.iter()
         .map(|d| {
-            let config = get_parse_config(year, &d.folder);
-            (d.day, config)
+            let config = get_parse_config(year, &d.folder)?;
+            Ok((d.day, config))
         })
-        .collect::<HashMap<_, _>>()
+        .collect()
 }
 
 pub async fn get_aoc_infos(
@@ -93,23 +94,35 @@ pub fn get_number_of_runs(matches: &ArgMatches) -> Result<usize, AocError> {
         .parse()?)
 }
 
-pub async fn prepare_args(ctx: &PipelineCtx, day_path: &Path, day: usize) -> Option<RunningArgs> {
-    let main = find_file(day_path, "main", Some(&REGISTER.compiler_exts()))?;
-    let input_path = day_path.join("input");
-
-    if !input_path.exists()
-        && (download_input_file(day as u32, ctx.year as i32, day_path).await).is_err()
-    {
-        return None;
+pub async fn prepare_args(
+    ctx: &PipelineCtx,
+    register: &Register,
+    day_path: &Path,
+    day: usize,
+) -> Result<RunningArgs, ErrorTypes> {
+    let main = find_main(
+        register.compiled_mains(day_path),
+        day_path,
+        ctx.lang.as_deref(),
+    )
+    .map_err(|err| ErrorTypes::NoMain(err.to_string()))?;
+    if !input_path(day_path, None).exists() {
+        let provider = get_provider().map_err(|_| ErrorTypes::InputDownload)?;
+        download_inputs(provider, ctx.year as i32, day as u32, day_path)
+            .await
+            .map_err(|_| ErrorTypes::InputDownload)?;
     }
 
-    Some(RunningArgs {
+    Ok(RunningArgs {
         release: true,
+        part: None,
+        mode: Mode::Tally,
         arguments: vec![],
         common: Common {
             day_folder: day_path.to_path_buf(),
-            input_file: input_path,
+            input_file: input_path(day_path, None),
             day: day as i32,
+            year: ctx.year as i32,
             file: main,
             root_folder: ctx.root.to_path_buf(),
         },
@@ -119,26 +132,22 @@ pub async fn prepare_args(ctx: &PipelineCtx, day_path: &Path, day: usize) -> Opt
 pub fn compile_day(
     day: usize,
     args: RunningArgs,
+    register: &Register,
     progress: &ProgressBar,
 ) -> Result<CompiledDay, (usize, ErrorTypes)> {
-    let Some(ext) = args
-        .common
-        .file
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|s| s.to_string())
+    let Some(compiler) = register.compiler_by_file(&args.common.day_folder, &args.common.file)
     else {
         progress.inc(1);
-        return Err((day, ErrorTypes::MissingExtension));
-    };
-
-    let Some(compiler) = REGISTER.compiler_by_extension(&ext) else {
-        progress.inc(1);
-        return Err((day, ErrorTypes::Unsupported(ext)));
+        let err = match args.common.file.extension() {
+            Some(ext) => ErrorTypes::Unsupported(ext.to_string_lossy().into_owned()),
+            None => ErrorTypes::MissingExtension,
+        };
+        return Err((day, err));
     };
 
+    let lang = register.lang(&args.common.day_folder, &args.common.file);
     let res = match compiler.compile(args) {
-        Ok(expr) => Ok(CompiledDay { day, expr }),
+        Ok(expr) => Ok(CompiledDay { day, lang, expr }),
         Err(err) => Err((day, ErrorTypes::Compiler(err.to_string()))),
     };
     progress.inc(1);
@@ -149,22 +158,30 @@ pub fn run_day(
     num_runs: usize,
     expr: Expression,
     config: &Config,
+    limits: Limits,
     progress: &ProgressBar,
 ) -> Result<RunRes, ErrorTypes> {
     let mut vec = Vec::new();
+    let mut usages = Vec::new();
     for _ in 0..num_runs {
         let expr = expr.clone();
 
         let (mut r, w) = std::io::pipe().unwrap();
         let (mut stdoutr, stdoutw) = std::io::pipe().unwrap();
-        let out = expr
-            .unchecked()
-            .stderr_file(w)
-            .stdout_file(stdoutw)
-            .run()
+        let started = Instant::now();
+        let handle = start_group(expr.unchecked().stderr_file(w).stdout_file(stdoutw))
             .map_err(ErrorTypes::DuctError)?;
 
-        if !out.status.success() {
+        let watchdog = Watchdog::start(handle.pids().first().copied(), limits);
+        let out = wait_with_usage(handle, started).map_err(ErrorTypes::DuctError);
+        if let Some(exceeded) = watchdog.finish() {
+            progress.finish_and_clear();
+            return Err(exceeded.into());
+        }
+        let (status, usage) = out?;
+        usages.push(usage);
+
+        if !status.success() {
             progress.finish_and_clear();
             let mut vec = Vec::new();
             r.read_to_end(&mut vec).expect("reading to vec");
@@ -172,7 +189,7 @@ pub fn run_day(
                 .expect("Getting stderr")
                 .to_owned();
 
-            return Err(ErrorTypes::Runtime(text));
+            return Err(ErrorTypes::Runtime(text, status.code()));
         }
 
         let mut stdout = Vec::new();
@@ -203,6 +220,17 @@ pub fn run_day(
         .copied()
         .collect::<Option<Vec<usize>>>()
         .map(|vals| vals.into_iter().sum::<usize>() / num_runs);
+
+    let average = |durations: Option<Vec<Duration>>| {
+        durations.map(|vals| vals.into_iter().sum::<Duration>() / num_runs as u32)
+    };
+    let usage = Usage {
+        wall: average(Some(usages.iter().map(|u| u.wall).collect())).unwrap_or_default(),
+        user: average(usages.iter().map(|u| u.user).collect()),
+        sys: average(usages.iter().map(|u| u.sys).collect()),
+        max_rss: usages.iter().filter_map(|u| u.max_rss).max(),
+    };
+
     let res = RunRes {
         p1: Answer {
             value: Some(vec[0].0.0.clone()),
@@ -212,6 +240,7 @@ pub fn run_day(
             value: vec[0].1.0.clone(),
             time: p2_time,
         },
+        usage,
     };
 
     Ok(res)
@@ -3,6 +3,8 @@ use std::path::Path;
 use regex::Regex;
 use serde::Deserialize;
 
+use crate::{error::AocError, util::file::read_toml};
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct TaskConfig {
     #[serde(with = "serde_regex")]
@@ -19,10 +21,8 @@ pub struct Config {
 }
 
 impl Config {
-    pub fn new(path: &Path) -> std::io::Result<Self> {
-        let toml_str = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&toml_str).map_err(std::io::Error::other)?;
-        Ok(config)
+    pub fn new(path: &Path) -> Result<Self, AocError> {
+        read_toml(path)
     }
 
     pub fn get_times(&self, output: &str) -> (Option<usize>, Option<usize>) {
@@ -1,26 +1,69 @@
-use std::io::{BufRead, BufReader};
+use std::{path::Path, process::ExitCode};
 
 use clap::ArgMatches;
-use duct::cmd;
 
-use crate::{error::AocError, util::get_day};
+use crate::{
+    error::AocError,
+    language::{Mode, Tool},
+    run::{print_output, start_tool},
+    util::{
+        file::{get_root_path, get_year_from_path},
+        get_days, get_lang,
+    },
+};
 
-pub async fn test(matches: &ArgMatches) -> Result<(), AocError> {
-    let day = get_day(matches)?;
-    let day = format!("day_{:02}", day);
+/// Runs the `test` command of the toolchain for the day and returns whether
+/// the tests passed
+async fn test_day(root: &Path, day: u32, lang: Option<&str>) -> Result<bool, AocError> {
+    let child = start_tool(root, day, Tool::Test, Mode::Test, lang).await?;
+    print_output(child.output(), false);
+    let (status, _) = child.wait()?;
 
-    let reader = cmd!(
-        "cargo", "test", "--color", "always", "--", "--color", "always"
-    )
-    .dir(day)
-    .stderr_to_stdout()
-    .reader()?;
+    Ok(status.success())
+}
+
+pub async fn test(matches: &ArgMatches) -> Result<ExitCode, AocError> {
+    let root = get_root_path()?;
+    let year = get_year_from_path(&root)?;
+    let days = get_days(matches, year)?;
+    let lang = get_lang(matches);
 
-    let reader = BufReader::new(reader);
-    let mut lines = reader.lines();
+    if let [day] = days.as_slice() {
+        return Ok(if test_day(&root, *day, lang).await? {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
+    }
 
-    while let Some(Ok(line)) = lines.next() {
-        println!("{}", line);
+    let mut results = Vec::new();
+    for day in days {
+        println!("\n\x1b[1m--- Day {:02} ---\x1b[0m", day);
+        let res = test_day(&root, day, lang).await;
+        if let Err(err) = &res {
+            println!("{}", err);
+        }
+        results.push((day, res));
     }
-    Ok(())
+
+    println!("\n\x1b[1m--- Summary ---\x1b[0m");
+    for (day, res) in &results {
+        match res {
+            Ok(true) => println!("Day {:02}: \x1b[0;32mok\x1b[0m", day),
+            Ok(false) => println!("Day {:02}: \x1b[0;31mFAILED\x1b[0m", day),
+            Err(err) => println!("Day {:02}: \x1b[0;31merror\x1b[0m: {}", day, err),
+        }
+    }
+
+    let passed = results
+        .iter()
+        .filter(|(_, res)| matches!(res, Ok(true)))
+        .count();
+    println!("{}/{} days passed", passed, results.len());
+
+    Ok(if passed == results.len() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
 }
@@ -1,20 +1,39 @@
 use clap::ArgMatches;
 
+#[cfg(feature = "browser")]
+use crate::util::browser::{Browser, get_session_cookie};
 use crate::{error::AocError, util::file::get_root_path};
 
+async fn set_token(token: &str) -> Result<(), AocError> {
+    let mut path = get_root_path()?;
+    path.push(".env");
+
+    tokio::fs::write(path, format!("AOC_TOKEN={token}"))
+        .await
+        .expect("Couldn't write to file");
+    Ok(())
+}
+
 pub async fn token(matches: &ArgMatches) -> Result<(), AocError> {
     if let Some(token) = matches.get_one::<String>("set") {
-        let mut path = get_root_path()?;
-        path.push(".env");
+        set_token(token).await?;
+        return Ok(());
+    }
 
-        tokio::fs::write(path, format!("AOC_TOKEN={token}"))
-            .await
-            .expect("Couldn't write to file");
-    } else {
-        println!(
-            "{}",
-            dotenv::var("AOC_TOKEN").unwrap_or_else(|_| "Could not find token".to_string())
-        );
+    #[cfg(feature = "browser")]
+    if let Some(browser) = matches
+        .get_one::<String>("from-browser")
+        .and_then(|name| Browser::from_name(name))
+    {
+        let token = get_session_cookie(browser)?;
+        set_token(&token).await?;
+        println!("Saved session token from {}", browser);
+        return Ok(());
     }
+
+    println!(
+        "{}",
+        dotenv::var("AOC_TOKEN").unwrap_or_else(|_| "Could not find token".to_string())
+    );
     Ok(())
 }
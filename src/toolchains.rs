@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use table_generator::{Column, Table};
+
+use crate::{
+    error::AocError,
+    util::file::{get_root_path, get_supported_languages},
+};
+
+/// Whether `program` can be started, either as a path or from `PATH`
+fn is_available(program: &str) -> bool {
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(program).is_file();
+    }
+
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&paths).any(|dir| {
+        dir.join(program).is_file()
+            || (cfg!(windows) && dir.join(format!("{}.exe", program)).is_file())
+    })
+}
+
+/// Lists the configured toolchains, the commands they support and whether the
+/// programs they need are available
+pub fn languages() -> Result<(), AocError> {
+    let root = get_root_path().unwrap_or_else(|_| PathBuf::from("."));
+    let config = get_supported_languages(&root, None)?;
+
+    let mut names = config.toolchain.keys().collect::<Vec<_>>();
+    names.sort_by_key(|name| (config.toolchain[*name].ext(), *name));
+
+    let mut table = Table::new("Toolchains".to_owned());
+    let mut name_col = Column::new("Toolchain");
+    let mut ext_col = Column::new("Ext");
+    let mut commands_col = Column::new("Commands");
+    let mut programs_col = Column::new("Programs");
+    let mut status_col = Column::new("Status");
+
+    for name in names {
+        let toolchain = &config.toolchain[name];
+        let active = config.extensions.get(toolchain.ext()) == Some(name);
+        let programs = toolchain.programs();
+        let missing = programs
+            .iter()
+            .filter(|program| !is_available(program))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        let markers = toolchain.markers();
+        name_col.add_row(vec![if active {
+            name.clone()
+        } else if !markers.is_empty() {
+            format!("{} (with {})", name, markers.join(", "))
+        } else {
+            format!("{} (inactive)", name)
+        }]);
+        ext_col.add_row(vec![toolchain.ext()]);
+        commands_col.add_row(vec![toolchain.commands().join(", ")]);
+        programs_col.add_row(vec![programs.join(", ")]);
+        status_col.add_row(vec![if missing.is_empty() {
+            "\x1b[0;32mok\x1b[0m".to_owned()
+        } else {
+            format!("\x1b[0;31mmissing\x1b[0m {}", missing.join(", "))
+        }]);
+    }
+
+    table.add_column(name_col);
+    table.add_column(ext_col);
+    table.add_column(commands_col);
+    table.add_column(programs_col);
+    table.add_column(status_col);
+    println!("{}", table);
+
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use std::{
+    env::home_dir,
+    path::{Path, PathBuf},
+};
+
+use aes::cipher::{BlockDecryptMut, KeyIvInit, block_padding::Pkcs7};
+use rusqlite::{Connection, OpenFlags};
+
+use crate::error::AocError;
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Browser {
+    Firefox,
+    Chromium,
+}
+
+impl Browser {
+    pub const NAMES: [&'static str; 2] = ["firefox", "chromium"];
+
+    pub fn from_name(name: &str) -> Option<Browser> {
+        match name {
+            "firefox" => Some(Browser::Firefox),
+            "chromium" => Some(Browser::Chromium),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Browser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Browser::Firefox => write!(f, "firefox"),
+            Browser::Chromium => write!(f, "chromium"),
+        }
+    }
+}
+
+struct Cookie {
+    value: String,
+    last_access: i64,
+}
+
+/// Finds the adventofcode.com `session` cookie in the local profiles of the
+/// given browser. If several profiles are logged in, the most recently used
+/// cookie wins.
+pub fn get_session_cookie(browser: Browser) -> Result<String, AocError> {
+    let home = home_dir().ok_or_else(|| AocError::BrowserError("Could not find home".into()))?;
+
+    let databases = match browser {
+        Browser::Firefox => firefox_databases(&home),
+        Browser::Chromium => chromium_databases(&home),
+    };
+    if databases.is_empty() {
+        return Err(AocError::BrowserError(format!(
+            "Could not find any {} profiles",
+            browser
+        )));
+    }
+
+    let mut cookies = Vec::new();
+    for db in databases {
+        let conn = open_read_only(&db)?;
+        match browser {
+            Browser::Firefox => cookies.extend(read_firefox_cookies(&conn)?),
+            Browser::Chromium => cookies.extend(read_chromium_cookies(&conn)?),
+        }
+    }
+
+    cookies
+        .into_iter()
+        .max_by_key(|c| c.last_access)
+        .map(|c| c.value)
+        .ok_or_else(|| {
+            AocError::BrowserError(format!(
+                "Could not find a session cookie for adventofcode.com in {}",
+                browser
+            ))
+        })
+}
+
+fn profile_files(base: &Path, files: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .flat_map(|profile| files.iter().map(move |file| profile.join(file)))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn firefox_databases(home: &Path) -> Vec<PathBuf> {
+    let bases = [
+        home.join(".mozilla/firefox"),
+        home.join("snap/firefox/common/.mozilla/firefox"),
+        home.join(".var/app/org.mozilla.firefox/.mozilla/firefox"),
+    ];
+
+    bases
+        .iter()
+        .flat_map(|base| profile_files(base, &["cookies.sqlite"]))
+        .collect()
+}
+
+fn chromium_databases(home: &Path) -> Vec<PathBuf> {
+    let bases = [
+        home.join(".config/chromium"),
+        home.join(".config/google-chrome"),
+        home.join("snap/chromium/common/chromium"),
+    ];
+
+    // Newer versions moved the database into a `Network` folder
+    bases
+        .iter()
+        .flat_map(|base| profile_files(base, &["Cookies", "Network/Cookies"]))
+        .collect()
+}
+
+fn open_read_only(path: &Path) -> Result<Connection, AocError> {
+    // The browser keeps the database locked while running. Opening it as
+    // immutable lets us read it anyway
+    let uri = format!("{}?immutable=1", sqlite_uri(path));
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI;
+    Ok(Connection::open_with_flags(uri, flags)?)
+}
+
+/// A `file:` URI for `path`, percent-escaping everything but unreserved
+/// characters and `/` so that e.g `?` and `#` stay part of the path
+fn sqlite_uri(path: &Path) -> String {
+    let mut uri = "file:".to_owned();
+    for byte in path.as_os_str().as_encoded_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(*byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+fn read_firefox_cookies(conn: &Connection) -> Result<Vec<Cookie>, AocError> {
+    let mut stmt = conn.prepare(
+        "SELECT value, lastAccessed FROM moz_cookies \
+         WHERE host LIKE '%adventofcode.com' AND name = 'session'",
+    )?;
+
+    let cookies = stmt
+        .query_map([], |row| {
+            Ok(Cookie {
+                value: row.get(0)?,
+                last_access: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(cookies)
+}
+
+fn read_chromium_cookies(conn: &Connection) -> Result<Vec<Cookie>, AocError> {
+    // Since version 24 the decrypted value is prefixed with a hash of the host
+    let version = conn
+        .query_row(
+            "SELECT CAST(value AS INTEGER) FROM meta WHERE key = 'version'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap_or(0);
+
+    let mut stmt = conn.prepare(
+        "SELECT value, encrypted_value, last_access_utc FROM cookies \
+         WHERE host_key LIKE '%adventofcode.com' AND name = 'session'",
+    )?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Vec<u8>>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|(value, encrypted, last_access)| {
+            let value = if value.is_empty() {
+                decrypt_chromium_value(&encrypted, version >= 24)?
+            } else {
+                value
+            };
+            Ok(Cookie { value, last_access })
+        })
+        .collect()
+}
+
+fn decrypt_chromium_value(encrypted: &[u8], has_host_hash: bool) -> Result<String, AocError> {
+    // `v11` values are encrypted with a key from the desktop keyring, which we
+    // don't support. `v10` values use the hard-coded "peanuts" key.
+    let Some(data) = encrypted.strip_prefix(b"v10") else {
+        return Err(AocError::BrowserError(
+            "Cookie is encrypted with a keyring key, which is not supported".into(),
+        ));
+    };
+
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(b"peanuts", b"saltysalt", 1, &mut key);
+    let iv = [b' '; 16];
+
+    let mut buf = data.to_vec();
+    let plain = Aes128CbcDec::new(&key.into(), &iv.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut buf)
+        .map_err(|_| AocError::BrowserError("Could not decrypt cookie".into()))?;
+
+    let plain = if has_host_hash {
+        plain.get(32..).unwrap_or_default()
+    } else {
+        plain
+    };
+
+    String::from_utf8(plain.to_vec())
+        .map_err(|_| AocError::BrowserError("Decrypted cookie is not valid utf-8".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::cipher::{BlockEncryptMut, KeyIvInit, block_padding::Pkcs7};
+
+    use super::{decrypt_chromium_value, sqlite_uri};
+
+    type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+
+    fn encrypt(plain: &[u8]) -> Vec<u8> {
+        let mut key = [0u8; 16];
+        pbkdf2::pbkdf2_hmac::<sha1::Sha1>(b"peanuts", b"saltysalt", 1, &mut key);
+        let iv = [b' '; 16];
+
+        let mut buf = vec![0u8; plain.len() + 16];
+        buf[..plain.len()].copy_from_slice(plain);
+        let encrypted = Aes128CbcEnc::new(&key.into(), &iv.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut buf, plain.len())
+            .unwrap();
+
+        [b"v10", encrypted].concat()
+    }
+
+    #[test]
+    fn test_decrypt_chromium_value() {
+        let encrypted = encrypt(b"53616c7465645f5f");
+        let value = decrypt_chromium_value(&encrypted, false).unwrap();
+        assert_eq!(value, "53616c7465645f5f");
+    }
+
+    #[test]
+    fn test_decrypt_chromium_value_with_host_hash() {
+        let mut plain = vec![0u8; 32];
+        plain.extend(b"53616c7465645f5f");
+
+        let value = decrypt_chromium_value(&encrypt(&plain), true).unwrap();
+        assert_eq!(value, "53616c7465645f5f");
+    }
+
+    #[test]
+    fn test_decrypt_chromium_value_keyring_unsupported() {
+        assert!(decrypt_chromium_value(b"v11abcdef", false).is_err());
+    }
+
+    #[test]
+    fn test_sqlite_uri_escapes_path() {
+        let uri = sqlite_uri(std::path::Path::new("/home/me/a b?#%/Cookies"));
+        assert_eq!(uri, "file:/home/me/a%20b%3F%23%25/Cookies");
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use reqwest::{
+    Request, Response, StatusCode,
+    header::{COOKIE, USER_AGENT},
+};
+use serde::{Deserialize, Serialize};
+
+use super::file::get_root_path;
+use crate::error::AocError;
+
+static CASSETTE_DIR: &str = ".cassettes";
+
+/// How requests to adventofcode.com are made, controlled by `AOC_HTTP`.
+/// Cassettes are stored in `AOC_CASSETTE_DIR`, or `.cassettes` in the root of
+/// the project
+pub enum HttpMode {
+    Live,
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+impl HttpMode {
+    pub fn from_env() -> Result<Self, AocError> {
+        let mode = dotenv::var("AOC_HTTP").unwrap_or_default();
+        match mode.as_str() {
+            "" | "live" => Ok(HttpMode::Live),
+            "record" => Ok(HttpMode::Record(cassette_dir())),
+            "replay" => Ok(HttpMode::Replay(cassette_dir())),
+            _ => Err(AocError::CassetteError(format!(
+                "AOC_HTTP must be one of live, record or replay, got {}",
+                mode
+            ))),
+        }
+    }
+}
+
+/// A recorded request and response pair
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    body: Option<String>,
+    status: u16,
+    response: String,
+    headers: BTreeMap<String, String>,
+}
+
+fn cassette_dir() -> PathBuf {
+    if let Ok(dir) = dotenv::var("AOC_CASSETTE_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    get_root_path()
+        .or_else(|_| std::env::current_dir())
+        .unwrap_or_default()
+        .join(CASSETTE_DIR)
+}
+
+fn request_body(req: &Request) -> Option<String> {
+    let bytes = req.body()?.as_bytes()?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// FNV-1a, which unlike the hasher of std is stable across Rust versions
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A readable name from the method, host and path, with a hash of the whole
+/// request, including the query and body, to tell apart requests that only
+/// differ in those or in characters the name leaves out
+fn cassette_name(req: &Request) -> String {
+    let url = req.url();
+    let readable = format!(
+        "{}_{}{}",
+        req.method(),
+        url.host_str().unwrap_or_default(),
+        url.path()
+    )
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+    .collect::<String>();
+
+    let key = format!(
+        "{} {}\n{}",
+        req.method(),
+        url,
+        request_body(req).unwrap_or_default()
+    );
+    format!("{}_{:016x}.toml", readable.trim_matches('_'), fnv1a(&key))
+}
+
+/// The headers of the request without the session token and the contact in
+/// the User-Agent, as cassettes may be committed
+fn redacted_headers(req: &Request) -> BTreeMap<String, String> {
+    req.headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == COOKIE {
+                "session=<redacted>".to_owned()
+            } else if name == USER_AGENT {
+                "<redacted>".to_owned()
+            } else {
+                value.to_str().unwrap_or_default().to_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+fn to_response(status: StatusCode, body: String) -> Response {
+    http::Response::builder()
+        .status(status)
+        .body(body)
+        .expect("status and body are always valid")
+        .into()
+}
+
+pub async fn record(
+    dir: &Path,
+    client: &reqwest::Client,
+    req: Request,
+) -> Result<Response, AocError> {
+    let path = dir.join(cassette_name(&req));
+    let method = req.method().to_string();
+    let url = req.url().to_string();
+    let body = request_body(&req);
+    let headers = redacted_headers(&req);
+
+    let res = client.execute(req).await?;
+    let status = res.status();
+    let text = res.text().await?;
+
+    let interaction = Interaction {
+        method,
+        url,
+        body,
+        status: status.as_u16(),
+        response: text.clone(),
+        headers,
+    };
+    let s = toml::to_string(&interaction).map_err(std::io::Error::other)?;
+
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(path, s).await?;
+
+    Ok(to_response(status, text))
+}
+
+pub async fn replay(dir: &Path, req: &Request) -> Result<Response, AocError> {
+    let path = dir.join(cassette_name(req));
+    let Ok(s) = tokio::fs::read_to_string(&path).await else {
+        return Err(AocError::CassetteError(format!(
+            "no recorded response for {} {} in {}",
+            req.method(),
+            req.url(),
+            dir.display()
+        )));
+    };
+
+    let interaction: Interaction = toml::from_str(&s)?;
+    if interaction.method != req.method().as_str()
+        || interaction.url != req.url().as_str()
+        || interaction.body != request_body(req)
+    {
+        return Err(AocError::CassetteError(format!(
+            "{} was recorded for {} {}, not {} {}",
+            path.display(),
+            interaction.method,
+            interaction.url,
+            req.method(),
+            req.url()
+        )));
+    }
+    let status = StatusCode::from_u16(interaction.status)
+        .map_err(|e| AocError::CassetteError(e.to_string()))?;
+
+    Ok(to_response(status, interaction.response))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    use super::{cassette_name, record, redacted_headers, replay};
+
+    fn get(url: &str) -> reqwest::Request {
+        reqwest::Client::new().get(url).build().unwrap()
+    }
+
+    #[test]
+    fn test_cassette_name_collisions() {
+        let names = [
+            "https://adventofcode.com/2024/day/1?part=1",
+            "https://adventofcode.com/2024/day/1?part=2",
+            "https://adventofcode.com/2024/day/1",
+            "https://adventofcode.com/2024/day_1",
+            "https://adventofcode.com/2024/day-1",
+        ]
+        .map(|url| cassette_name(&get(url)));
+
+        for (i, name) in names.iter().enumerate() {
+            assert!(
+                name.starts_with("GET_adventofcode_com_2024_day"),
+                "{}",
+                name
+            );
+            assert!(!names[i + 1..].contains(name), "{}", name);
+        }
+        assert_eq!(
+            cassette_name(&get("https://adventofcode.com/2024/day/1?part=1")),
+            names[0]
+        );
+    }
+
+    #[test]
+    fn test_redacted_headers() {
+        let req = reqwest::Client::new()
+            .get("https://adventofcode.com/2024/day/1")
+            .header("Cookie", "session=secret")
+            .header("User-Agent", "cargo-aoc by me@example.com")
+            .header("Accept", "text/html")
+            .build()
+            .unwrap();
+
+        let headers = redacted_headers(&req);
+        assert_eq!(headers["cookie"], "session=<redacted>");
+        assert_eq!(headers["user-agent"], "<redacted>");
+        assert_eq!(headers["accept"], "text/html");
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 4096];
+            let _ = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello",
+                )
+                .unwrap();
+        });
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("cassettes");
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let url = format!("http://{}/2024/day/1?part=1", addr);
+
+        let res = record(&dir, &client, get(&url)).await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "hello");
+        server.join().unwrap();
+
+        let res = replay(&dir, &get(&url)).await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "hello");
+
+        let other = format!("http://{}/2024/day/1?part=2", addr);
+        assert!(replay(&dir, &get(&other)).await.is_err());
+    }
+}
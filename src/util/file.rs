@@ -1,21 +1,30 @@
 use std::{
-    collections::HashMap,
     env::home_dir,
     ffi::OsStr,
+    io::{Read, Write},
     path::{Path, PathBuf},
+    sync::{
+        Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use chrono::Datelike;
 use clap::ArgMatches;
 use regex::Regex;
-use reqwest::StatusCode;
 
-use super::request::AocRequest;
-use crate::{error::AocError, language::REGISTER, task_config::Config};
+use super::{Task, get_part};
+use crate::{
+    error::AocError,
+    language::{Main, Register},
+    provider::input_path,
+    task_config::Config,
+};
 
 static PARSE_FILE: &str = ".parse.toml";
 static LANGUAGE_FILE: &str = ".languages.toml";
-use crate::language::{Common, RunningArgs};
+static STRICT: AtomicBool = AtomicBool::new(false);
+use crate::language::{Common, Mode, RunningArgs};
 
 pub fn get_day_from_path() -> Result<Option<u32>, AocError> {
     let get_day = |s: &str| -> Option<u32> {
@@ -37,13 +46,11 @@ pub fn get_day_from_path() -> Result<Option<u32>, AocError> {
     let mut cwd = std::env::current_dir()?;
 
     loop {
-        let name = cwd.file_name();
-        let name = name
-            .ok_or(AocError::InvalidRunDay)?
-            .to_str()
-            .ok_or(AocError::InvalidRunDay)?;
-
-        if let Some(day) = get_day(name) {
+        if let Some(day) = cwd
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(get_day)
+        {
             return Ok(Some(day));
         }
         if !cwd.pop() {
@@ -147,55 +154,114 @@ pub async fn day_path<P: AsRef<Path>>(root: P, day: u32) -> Result<std::path::Pa
     Err(Error::new(ErrorKind::NotFound, err_text).into())
 }
 
-pub async fn download_input_file(day: u32, year: i32, dir: &Path) -> Result<(), AocError> {
-    let url = format!("https://adventofcode.com/{}/day/{}/input", year, day);
-    let res = AocRequest::new().get(url).await?;
+/// Directory for the global configuration, i.e `~/.config/cargo-aoc`
+pub fn get_config_dir() -> Option<PathBuf> {
+    home_dir().map(|path| path.join(".config").join("cargo-aoc"))
+}
 
-    if res.status() != StatusCode::OK {
-        return Err(AocError::DownloadError(format!(
-            "Couldn't download input for year: {} and day: {}",
-            year, day
-        )));
+/// The `.languages.toml` in the day folder, if any
+pub fn get_day_languages(day: &Path) -> Option<PathBuf> {
+    Some(day.join(LANGUAGE_FILE)).filter(|path| path.exists())
+}
+
+/// Makes invalid configuration files an error instead of a warning, see
+/// `--strict`
+pub fn set_strict(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+/// Fails with `err` in strict mode, and else warns about it once so that the
+/// invalid configuration can be skipped
+pub fn config_error(err: AocError) -> Result<(), AocError> {
+    static WARNED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    if STRICT.load(Ordering::Relaxed) {
+        return Err(err);
     }
 
-    let bytes = res.bytes().await?;
-    tokio::fs::write(dir.join("input"), bytes).await?;
+    let message = err.to_string();
+    let mut warned = WARNED.lock().unwrap();
+    if !warned.contains(&message) {
+        eprintln!("warning: {}", message);
+        warned.push(message);
+    }
     Ok(())
 }
 
-pub fn get_supported_languages(root: &Path) -> crate::language::Config {
+/// Reads a toml file, with the line and column of parse errors
+pub fn read_toml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, AocError> {
+    let s = std::fs::read_to_string(path).map_err(|err| AocError::ConfigError {
+        location: path.display().to_string(),
+        message: err.to_string(),
+    })?;
+    parse_toml(path, &s)
+}
+
+/// Parses the content `s` of the toml file at `path`
+pub fn parse_toml<T: serde::de::DeserializeOwned>(path: &Path, s: &str) -> Result<T, AocError> {
+    toml::from_str(s).map_err(|err| {
+        let location = match err.span() {
+            Some(span) => {
+                let before = &s[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let column = before
+                    .rsplit('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .count()
+                    + 1;
+                format!("{}:{}:{}", path.display(), line, column)
+            }
+            None => path.display().to_string(),
+        };
+        AocError::ConfigError {
+            location,
+            message: err.message().trim().to_owned(),
+        }
+    })
+}
+
+/// The toolchains of the built-in, global and year `.languages.toml`, and
+/// the one of the day if given, in order of precedence
+pub fn get_supported_languages(
+    root: &Path,
+    day: Option<&Path>,
+) -> Result<crate::language::Config, AocError> {
     let root_lang = root.join(LANGUAGE_FILE);
-    let config_lang = home_dir()
-        .map(|path| path.join(".config").join("cargo-aoc").join(LANGUAGE_FILE))
+    let config_lang = get_config_dir()
+        .map(|path| path.join(LANGUAGE_FILE))
         .filter(|path| path.exists());
 
-    let configs = [config_lang, root_lang.exists().then_some(root_lang)];
+    let configs = [
+        config_lang,
+        root_lang.exists().then_some(root_lang),
+        day.and_then(get_day_languages),
+    ];
 
-    use crate::language::Config as C;
+    use crate::language::{Config, Layer};
 
     let s = include_str!("../../.languages.toml");
-    let default_config: C = toml::from_str(s).expect("Error in the default language config");
+    let default_layer: Layer = toml::from_str(s).expect("Error in the default language config");
 
-    let iter = std::iter::once(default_config).chain(
-        configs
-            .into_iter()
-            .flatten()
-            .flat_map(|path| C::from_file(&path)),
-    );
-
-    let mut toolchains = HashMap::new();
-
-    for config in iter {
-        for (ext, toolchain) in config.toolchain {
-            toolchains.insert(ext, toolchain);
+    let mut layers = vec![default_layer];
+    for path in configs.into_iter().flatten() {
+        match Layer::from_file(&path) {
+            Ok(layer) => layers.push(layer),
+            Err(err) => config_error(err)?,
         }
     }
-    crate::language::Config {
-        toolchain: toolchains,
+
+    let (config, errors) = Config::from_layers(layers);
+    for err in errors {
+        config_error(err)?;
     }
+    Ok(config)
 }
 
-pub fn get_parse_config(root: &Path, day: &Path) -> Config {
+/// The `.parse.toml` of the day, year or global config, or else the default
+/// that takes the first two lines as the answers
+pub fn get_parse_config(root: &Path, day: &Path) -> Result<Config, AocError> {
     let f = || {
         if let Some(file) = find_file(day, PARSE_FILE, None) {
             return Some(file);
@@ -206,10 +272,7 @@ pub fn get_parse_config(root: &Path, day: &Path) -> Config {
             return Some(root);
         }
 
-        let config = home_dir()?
-            .join(".config")
-            .join("cargo-aoc")
-            .join(PARSE_FILE);
+        let config = get_config_dir()?.join(PARSE_FILE);
         if config.exists() {
             return Some(config);
         }
@@ -217,49 +280,183 @@ pub fn get_parse_config(root: &Path, day: &Path) -> Config {
         None
     };
 
-    f().and_then(|path| Config::new(&path).ok())
-        .unwrap_or_default()
+    let Some(path) = f() else {
+        return Ok(Config::default());
+    };
+    match Config::new(&path) {
+        Ok(config) => Ok(config),
+        Err(err) => config_error(err).map(|_| Config::default()),
+    }
 }
 
-pub fn get_input_file(matches: &ArgMatches) -> &str {
-    if matches.get_flag("test") {
-        "test"
-    } else {
-        "input"
+static STDIN_INPUT: OnceLock<PathBuf> = OnceLock::new();
+static TEXT_INPUT: OnceLock<PathBuf> = OnceLock::new();
+static TEMP_DIR: Mutex<Option<tempfile::TempDir>> = Mutex::new(None);
+
+/// Writes input that doesn't come from a file to a temporary file, for
+/// languages that take a path. The file is created in a private directory
+/// that is removed by [`remove_temp_inputs`]
+fn write_temp_input(name: &str, s: &str) -> Result<PathBuf, AocError> {
+    let mut dir = TEMP_DIR.lock().unwrap();
+    if dir.is_none() {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("cargo-aoc-");
+        #[cfg(unix)]
+        builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+        *dir = Some(builder.tempdir()?);
     }
+    let path = dir.as_ref().unwrap().path().join(name);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&path)?.write_all(s.as_bytes())?;
+    Ok(path)
 }
 
-pub async fn get_running_args(matches: &ArgMatches) -> Result<RunningArgs, AocError> {
-    let day = super::get_day(matches)?;
-    let root = get_root_path()?;
-    let day_path = day_path(&root, day).await?;
+/// Removes the temporary input files written for the run
+pub fn remove_temp_inputs() {
+    TEMP_DIR.lock().unwrap().take();
+}
 
-    let main = find_file(&day_path, "main", Some(&REGISTER.runner_exts())).unwrap();
+/// Reads the input from stdin once, so that running several days gets the
+/// same input
+fn stdin_input() -> Result<PathBuf, AocError> {
+    if let Some(path) = STDIN_INPUT.get() {
+        return Ok(path.clone());
+    }
 
-    let input_file = get_input_file(matches);
+    let mut s = String::new();
+    std::io::stdin().read_to_string(&mut s)?;
+    let path = write_temp_input("stdin", &s)?;
+    Ok(STDIN_INPUT.get_or_init(|| path).clone())
+}
 
-    let mut input = day_path.clone();
-    input.push(input_file);
+/// Writes the input given with `--input-text` once, so that running several
+/// days, languages or watch iterations gets the same file
+fn text_input(text: &str) -> Result<PathBuf, AocError> {
+    if let Some(path) = TEXT_INPUT.get() {
+        return Ok(path.clone());
+    }
 
-    let trailing_args = matches
-        .get_many::<String>("args")
-        .unwrap_or_default()
-        .cloned()
-        .collect::<Vec<_>>();
+    let text = if text.ends_with('\n') {
+        text.to_owned()
+    } else {
+        format!("{}\n", text)
+    };
+    let path = write_temp_input("text", &text)?;
+    Ok(TEXT_INPUT.get_or_init(|| path).clone())
+}
+
+/// The input given with `--input` or `--input-text`, or else the `test` or
+/// `input` file of the day
+pub fn get_input_path(matches: &ArgMatches, day_path: &Path) -> Result<PathBuf, AocError> {
+    if let Ok(Some(text)) = matches.try_get_one::<String>("input-text") {
+        return text_input(text);
+    }
+
+    let path = match matches.try_get_one::<String>("input").ok().flatten() {
+        Some(path) if path == "-" => return stdin_input(),
+        Some(path) => std::path::absolute(path)?,
+        None if matches.get_flag("test") => return Ok(day_path.join("test")),
+        None => return Ok(input_path(day_path, get_part(matches).map(Task::number))),
+    };
+
+    if !path.is_file() {
+        return Err(AocError::StdIoErr(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("could not find input file {}", path.display()),
+        )));
+    }
+    Ok(path)
+}
+
+/// Arguments for the day with its downloaded input, using the
+/// implementation in `lang` if given
+pub async fn get_day_args(
+    root: &Path,
+    day: u32,
+    mode: Mode,
+    lang: Option<&str>,
+) -> Result<RunningArgs, AocError> {
+    let day_path = day_path(root, day).await?;
+    let register = Register::for_day(root, &day_path)?;
+    let main = find_main(register.mains(&day_path), &day_path, lang)?;
 
     Ok(RunningArgs {
-        arguments: trailing_args,
-        release: false,
+        mode,
         common: Common {
             file: main,
             day: day as i32,
+            year: get_year_from_path(root)?,
+            input_file: input_path(&day_path, None),
             day_folder: day_path,
-            root_folder: root,
-            input_file: input,
+            root_folder: root.to_path_buf(),
         },
+        ..Default::default()
     })
 }
 
+pub async fn get_running_args(
+    matches: &ArgMatches,
+    day: u32,
+    lang: Option<&str>,
+) -> Result<RunningArgs, AocError> {
+    let root = get_root_path()?;
+    let mut args = get_day_args(&root, day, Mode::Run, lang).await?;
+    let day_path = &args.common.day_folder;
+
+    let trailing_args = matches
+        .get_many::<String>("args")
+        .unwrap_or_default()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    args.release = matches.get_flag("release")
+        || (!matches.get_flag("debug") && get_supported_languages(&root, Some(day_path))?.release);
+    args.common.input_file = get_input_path(matches, day_path)?;
+    args.arguments = trailing_args;
+    args.part = get_part(matches);
+    if matches.get_flag("test") {
+        args.mode = Mode::Test;
+    }
+
+    Ok(args)
+}
+
+/// The language of a main file, i.e its extension
+pub fn get_file_lang(file: &Path) -> String {
+    file.extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The main file of the day in `lang`, which must be given when the day is
+/// implemented in several languages
+pub fn find_main(
+    mut mains: Vec<Main>,
+    day_path: &Path,
+    lang: Option<&str>,
+) -> Result<PathBuf, AocError> {
+    if let Some(lang) = lang {
+        return mains
+            .into_iter()
+            .find(|main| main.lang == lang)
+            .map(|main| main.file)
+            .ok_or_else(|| AocError::MissingLang(day_path.to_path_buf(), lang.to_owned()));
+    }
+
+    match mains.len() {
+        0 => Err(AocError::MissingMain(day_path.to_path_buf())),
+        1 => Ok(mains.remove(0).file),
+        _ => Err(AocError::AmbiguousMain(
+            day_path.to_path_buf(),
+            mains.into_iter().map(|main| main.lang).collect(),
+        )),
+    }
+}
+
 pub fn find_file(
     start_dir: &Path,
     filename: &str,
@@ -308,3 +505,30 @@ pub fn find_file(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_text() {
+        let matches = clap::Command::new("run")
+            .arg(clap::Arg::new("input-text").long("input-text"))
+            .get_matches_from(["run", "--input-text", "1 2 3"]);
+
+        let path = get_input_path(&matches, Path::new("day_01")).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1 2 3\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(path.parent().unwrap()), 0o700);
+        }
+        // Resolving it again, e.g for the next day, reuses the file
+        assert_eq!(get_input_path(&matches, Path::new("day_02")).unwrap(), path);
+
+        remove_temp_inputs();
+        assert!(!path.parent().unwrap().exists());
+    }
+}
@@ -1,27 +1,40 @@
 use clap::Arg;
-use reqwest::StatusCode;
 use std::path::PathBuf;
 
 use chrono::Datelike;
 use clap::ArgMatches;
 use file::get_root_path;
 
-use self::{
-    file::{day_path, get_day_from_path},
-    request::AocRequest,
+use self::file::{day_path, get_day_from_path};
+use crate::{
+    error::AocError,
+    provider::{PuzzleInfo, get_provider},
 };
-use crate::error::AocError;
 
+#[cfg(feature = "browser")]
+pub mod browser;
+pub mod cassette;
 pub mod file;
+pub mod page;
 pub mod request;
 #[cfg(feature = "submit")]
 pub mod submit;
-#[derive(Eq, PartialEq, Clone, Copy)]
+pub mod user_config;
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum Task {
     One,
     Two,
 }
 
+impl Task {
+    pub fn number(self) -> u8 {
+        match self {
+            Task::One => 1,
+            Task::Two => 2,
+        }
+    }
+}
+
 impl std::fmt::Display for Task {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -31,23 +44,84 @@ impl std::fmt::Display for Task {
     }
 }
 
-pub fn get_day(matches: &ArgMatches) -> Result<u32, AocError> {
+/// The part given with `--part`, if any
+pub fn get_part(matches: &ArgMatches) -> Option<Task> {
+    match matches.try_get_one::<String>("part").ok()??.as_str() {
+        "1" => Some(Task::One),
+        "2" => Some(Task::Two),
+        _ => None,
+    }
+}
+
+/// The language given with `--lang`, if any
+pub fn get_lang(matches: &ArgMatches) -> Option<&str> {
+    matches
+        .try_get_one::<String>("lang")
+        .ok()
+        .flatten()
+        .map(String::as_str)
+}
+
+/// Limits answers or times parsed from the output to `part`. A solution only
+/// prints one answer when running a single part, which is parsed as part one
+/// by the default config
+pub fn select_part<T>(parts: (Option<T>, Option<T>), part: Option<Task>) -> (Option<T>, Option<T>) {
+    match (part, parts) {
+        (None, parts) => parts,
+        (Some(Task::One), (p1, _)) => (p1, None),
+        (Some(Task::Two), (p1, p2)) => (None, p2.or(p1)),
+    }
+}
+
+/// The day given with `day`, which defaults to the day folder we are in (see
+/// [`get_day_argument`])
+pub fn get_day(matches: &ArgMatches, year: i32) -> Result<u32, AocError> {
     let day = matches
         .get_one::<String>("day")
         .ok_or(AocError::ArgMatches)?
         .parse::<u32>()?;
-    if !(1..=25).contains(&day) {
-        Err(AocError::InvalidRunDay)
-    } else {
-        let source = matches.value_source("day").unwrap();
-        if source == clap::parser::ValueSource::DefaultValue
-            && let Ok(Some(day)) = get_day_from_path()
-        {
-            return Ok(day);
+    let last_day = get_provider()?.days(year);
+    if !(1..=last_day).contains(&day) {
+        return Err(AocError::InvalidRunDay(last_day));
+    }
+    Ok(day)
+}
+
+/// Parses days on the form `5`, `1..=5`, `1..6` or `3,7,9`, or any
+/// combination of them separated by commas, in a year of `last_day` days
+pub fn parse_days(s: &str, last_day: u32) -> Result<Vec<u32>, AocError> {
+    let mut days = Vec::new();
+    for part in s.split(',').map(str::trim) {
+        if let Some((start, end)) = part.split_once("..=") {
+            days.extend(start.trim().parse::<u32>()?..=end.trim().parse::<u32>()?);
+        } else if let Some((start, end)) = part.split_once("..") {
+            days.extend(start.trim().parse::<u32>()?..end.trim().parse::<u32>()?);
+        } else {
+            days.push(part.parse::<u32>()?);
         }
+    }
+
+    if days.is_empty() || days.iter().any(|day| !(1..=last_day).contains(day)) {
+        return Err(AocError::InvalidRunDay(last_day));
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
 
-        Ok(day)
+/// Like [`get_day`], but allows several days and `--all`
+pub fn get_days(matches: &ArgMatches, year: i32) -> Result<Vec<u32>, AocError> {
+    let provider = get_provider()?;
+    if matches.get_flag("all") {
+        return provider.calendar(year);
     }
+
+    parse_days(
+        matches
+            .get_one::<String>("day")
+            .ok_or(AocError::ArgMatches)?,
+        provider.days(year),
+    )
 }
 
 pub fn get_time_symbol() -> String {
@@ -60,6 +134,8 @@ pub struct AocInfo {
     pub title: String,
     pub part1_answer: Option<String>,
     pub part2_answer: Option<String>,
+    /// Whether every part is solved, so that the answers won't change
+    pub solved: bool,
 }
 
 impl AocInfo {
@@ -68,48 +144,27 @@ impl AocInfo {
     }
 }
 
+/// The answers of the first two parts, which are the ones `run`, `assert` and
+/// `tally` check
+impl From<PuzzleInfo> for AocInfo {
+    fn from(info: PuzzleInfo) -> Self {
+        let solved = !info.parts.is_empty() && info.parts.iter().all(|part| part.solved);
+        let mut answers = info.parts.into_iter().map(|part| part.answer);
+        AocInfo {
+            title: info.title,
+            part1_answer: answers.next().flatten(),
+            part2_answer: answers.next().flatten(),
+            solved,
+        }
+    }
+}
+
 pub async fn get_day_title_and_answers(day: u32, year: u32) -> Result<AocInfo, AocError> {
     if let Ok(cache) = read_cache_answers(day).await {
         return Ok(cache);
     }
 
-    let url = format!("https://adventofcode.com/{}/day/{}", year, day);
-
-    let res = AocRequest::new().get(&url).await?;
-    if !res.status().is_success() {
-        return Err(AocError::ApiError(format!(
-            "Could not get day info from {}: {}",
-            url,
-            res.status()
-        )));
-    }
-
-    let text = res.text().await?;
-
-    let h2 = "<h2>--- ";
-    let idx1 = text.find(h2).unwrap() + h2.len();
-    let idx2 = text[idx1..].find(" ---</h2>").unwrap();
-    let (_, title) = text[idx1..idx1 + idx2].split_once(": ").unwrap();
-
-    let search = "Your puzzle answer was <code>";
-    let mut iter = text
-        .lines()
-        .filter(|&line| line.contains(search))
-        .map(|line| {
-            let code_end = "</code>";
-            let idx = line.find(search).unwrap() + search.len();
-            let end = line[idx..].find(code_end).unwrap();
-
-            line[idx..idx + end].to_owned()
-        });
-    let a1 = iter.next();
-    let a2 = iter.next();
-
-    let info = AocInfo {
-        title: title.to_owned(),
-        part1_answer: a1,
-        part2_answer: a2,
-    };
+    let info = AocInfo::from(get_provider()?.info(year as i32, day).await?);
 
     // Ignore possible errors during cache write
     let _ = write_cache_answers(day, &info).await;
@@ -125,7 +180,9 @@ async fn get_cache_path(day: u32) -> Result<PathBuf, AocError> {
 
 pub async fn write_cache_answers(day: u32, info: &AocInfo) -> Result<(), AocError> {
     let path = get_cache_path(day).await?;
-    if let (Some(a1), Some(a2)) = (&info.part1_answer, &info.part2_answer) {
+    if info.solved {
+        let answer = |answer: &Option<String>| answer.clone().unwrap_or_default();
+        let (a1, a2) = (answer(&info.part1_answer), answer(&info.part2_answer));
         tokio::fs::write(path, format!("{}\n{}\n{}", info.title, a1, a2)).await?;
     }
 
@@ -136,13 +193,51 @@ pub async fn read_cache_answers(day: u32) -> Result<AocInfo, AocError> {
     let path = get_cache_path(day).await?;
     let res = tokio::fs::read_to_string(path).await?;
     let lines = res.lines().collect::<Vec<_>>();
+    let answer = |i: usize| {
+        lines
+            .get(i)
+            .filter(|answer| !answer.is_empty())
+            .map(|answer| answer.to_string())
+    };
     Ok(AocInfo {
         title: lines[0].to_owned(),
-        part1_answer: Some(lines[1].to_owned()),
-        part2_answer: Some(lines[2].to_owned()),
+        part1_answer: answer(1),
+        part2_answer: answer(2),
+        solved: true,
     })
 }
 
+/// `day`, accepting ranges of days, and `--all` for commands that can run
+/// several days
+pub fn get_days_arguments(action: &str) -> [Arg; 2] {
+    [
+        get_day_argument()
+            .required(false)
+            .required_unless_present("all")
+            .help(format!("Day(s) to {}, e.g 5, 1..=5 or 3,7,9", action)),
+        Arg::new("all")
+            .long("all")
+            .required(false)
+            .action(clap::ArgAction::SetTrue)
+            .conflicts_with("day")
+            .help(format!("{} all days of the year", capitalize(action))),
+    ]
+}
+
+pub fn lang_argument() -> Arg {
+    Arg::new("lang").long("lang").required(false).help(
+        "Use the implementation in this language (its extension, e.g py) when a day has several",
+    )
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 pub fn get_day_argument() -> Arg {
     let now = chrono::Utc::now();
     let current_year = now.year();
@@ -167,30 +262,11 @@ pub fn get_day_argument() -> Arg {
     Arg::new("day").short('d').required(true)
 }
 
-pub async fn verify_token() -> Result<(), AocError> {
-    const URL: &str = "https://adventofcode.com/2015/day/1/input";
-    let token = dotenv::var("AOC_TOKEN")?;
-    let token = token.replace("session=", "");
-
-    let client = reqwest::Client::new();
-    let res = client
-        .get(URL)
-        .header("Cookie", &format!("session={}", token))
-        .send()
-        .await;
-
-    match res {
-        Ok(res) => match res.status() {
-            StatusCode::OK => Ok(()),
-            _ => Err(AocError::InvalidTokenError(res.status().to_string())),
-        },
-        Err(err) => Err(AocError::ApiError(err.to_string())),
-    }
-}
-
 #[cfg(test)]
 mod tests {
+    use crate::provider::{PartInfo, PuzzleInfo};
     use crate::task_config::{Config, TaskConfig};
+    use crate::util::{AocInfo, Task, file::parse_toml, parse_days, select_part};
 
     use regex::Regex;
 
@@ -277,4 +353,71 @@ bar
         assert_eq!(a1, Some("foo".into()));
         assert_eq!(a2, Some("bar".into()));
     }
+
+    #[test]
+    fn test_parse_config_error_location() {
+        let s = "[task_one]\nanswer = '(\\d+'\n\n[task_two]\nanswer = '(.*)'\n";
+        let err = parse_toml::<Config>(std::path::Path::new(".parse.toml"), s).unwrap_err();
+
+        let err = err.to_string();
+        assert!(
+            err.starts_with("Invalid config .parse.toml:2:10: "),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_info_from_three_parts() {
+        let part = |answer: Option<&str>| PartInfo {
+            answer: answer.map(str::to_owned),
+            solved: answer.is_some(),
+        };
+        let info = AocInfo::from(PuzzleInfo {
+            title: "Quest".to_owned(),
+            parts: vec![part(Some("1")), part(None), part(Some("3"))],
+        });
+
+        assert_eq!(info.part1_answer.as_deref(), Some("1"));
+        assert_eq!(info.part2_answer, None);
+        assert!(!info.solved);
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("5", 25).unwrap(), vec![5]);
+        assert_eq!(parse_days("1..=3", 25).unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_days("1..3", 25).unwrap(), vec![1, 2]);
+        assert_eq!(parse_days("9,3,7", 25).unwrap(), vec![3, 7, 9]);
+        assert_eq!(parse_days("1..=2, 2, 10", 25).unwrap(), vec![1, 2, 10]);
+    }
+
+    #[test]
+    fn test_parse_days_invalid() {
+        assert!(parse_days("0..=3", 25).is_err());
+        assert!(parse_days("24..=26", 25).is_err());
+        assert!(parse_days("3..1", 25).is_err());
+        assert!(parse_days("a", 25).is_err());
+        assert!(parse_days("12", 12).is_ok());
+        assert!(parse_days("10..=13", 12).is_err());
+    }
+
+    #[test]
+    fn test_select_part() {
+        let config = Config::default();
+        let (a1, a2) = config.get_answers("67890");
+
+        assert_eq!(
+            select_part((a1.clone(), a2.clone()), None),
+            (a1.clone(), None)
+        );
+        assert_eq!(
+            select_part((a1.clone(), a2.clone()), Some(Task::One)),
+            (Some("67890".to_owned()), None)
+        );
+        assert_eq!(
+            select_part((a1, a2), Some(Task::Two)),
+            (None, Some("67890".to_owned()))
+        );
+    }
 }
@@ -0,0 +1,181 @@
+use scraper::{ElementRef, Html, Selector};
+use thiserror::Error;
+
+use crate::provider::{PartInfo, PuzzleInfo};
+
+#[derive(Error, Debug)]
+pub enum PageError {
+    #[error("not a puzzle page, is the session token valid?")]
+    NotAPuzzlePage,
+
+    #[error("could not find the puzzle title")]
+    MissingTitle,
+
+    #[error("malformed puzzle title `{0}`")]
+    MalformedTitle(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stars {
+    None,
+    One,
+    Two,
+}
+
+/// The parts of a puzzle page we care about, e.g
+/// `https://adventofcode.com/2015/day/1`
+#[derive(Debug, Clone)]
+pub struct PuzzlePage {
+    pub title: String,
+    /// The text of each part that is visible. Part two is only visible after
+    /// solving part one
+    pub parts: Vec<String>,
+    /// Answers for the parts that are solved, in order
+    pub answers: Vec<String>,
+    pub stars: Stars,
+}
+
+fn selector(s: &str) -> Selector {
+    Selector::parse(s).expect("selector should be valid")
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect::<String>()
+}
+
+impl PuzzlePage {
+    pub fn parse(html: &str) -> Result<Self, PageError> {
+        let document = Html::parse_document(html);
+
+        let articles = document
+            .select(&selector("article.day-desc"))
+            .collect::<Vec<_>>();
+        let Some(first) = articles.first() else {
+            return Err(PageError::NotAPuzzlePage);
+        };
+
+        let heading = first
+            .select(&selector("h2"))
+            .next()
+            .map(text)
+            .ok_or(PageError::MissingTitle)?;
+        let title = heading
+            .trim()
+            .trim_start_matches("---")
+            .trim_end_matches("---")
+            .trim()
+            .split_once(": ")
+            .map(|(_, title)| title.trim().to_owned())
+            .ok_or_else(|| PageError::MalformedTitle(heading.clone()))?;
+
+        let parts = articles
+            .iter()
+            .map(|article| text(*article).trim().to_owned())
+            .collect();
+
+        let answers = document
+            .select(&selector("main > p"))
+            .filter(|p| text(*p).trim_start().starts_with("Your puzzle answer was"))
+            .filter_map(|p| p.select(&selector("code")).next().map(text))
+            .collect::<Vec<_>>();
+
+        let success = document
+            .select(&selector("p.day-success"))
+            .next()
+            .map(text)
+            .unwrap_or_default();
+        let stars = if success.starts_with("Both parts") {
+            Stars::Two
+        } else if success.starts_with("The first half") || !answers.is_empty() {
+            Stars::One
+        } else {
+            Stars::None
+        };
+
+        Ok(PuzzlePage {
+            title,
+            parts,
+            answers,
+            stars,
+        })
+    }
+}
+
+impl From<PuzzlePage> for PuzzleInfo {
+    /// Every day has two parts, also before part two is visible and on the
+    /// last day, whose second part gives a star without an answer
+    fn from(page: PuzzlePage) -> Self {
+        let stars = match page.stars {
+            Stars::None => 0,
+            Stars::One => 1,
+            Stars::Two => 2,
+        };
+        let mut answers = page.answers.into_iter();
+        PuzzleInfo {
+            title: page.title,
+            parts: (0..page.parts.len().max(2))
+                .map(|i| PartInfo {
+                    answer: answers.next(),
+                    solved: i < stars,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageError, PuzzlePage, Stars};
+    use crate::provider::PuzzleInfo;
+
+    #[test]
+    fn test_parse_both_parts_solved() {
+        let page = PuzzlePage::parse(include_str!("../../tests/pages/2015_day_01.html")).unwrap();
+
+        assert_eq!(page.title, "Not Quite Lisp");
+        assert_eq!(page.parts.len(), 2);
+        assert!(page.parts[1].starts_with("--- Part Two ---"));
+        assert_eq!(page.answers, vec!["232", "1783"]);
+        assert_eq!(page.stars, Stars::Two);
+    }
+
+    #[test]
+    fn test_parse_first_part_solved() {
+        let page = PuzzlePage::parse(include_str!("../../tests/pages/2023_day_05.html")).unwrap();
+
+        assert_eq!(page.title, "If You Give A Seed A Fertilizer");
+        assert_eq!(page.parts.len(), 2);
+        assert_eq!(page.answers, vec!["35"]);
+        assert_eq!(page.stars, Stars::One);
+    }
+
+    #[test]
+    fn test_parse_last_day_has_single_answer() {
+        let page = PuzzlePage::parse(include_str!("../../tests/pages/2020_day_25.html")).unwrap();
+
+        assert_eq!(page.title, "Combo Breaker");
+        assert_eq!(page.answers, vec!["14897079"]);
+        assert_eq!(page.stars, Stars::Two);
+
+        let info = PuzzleInfo::from(page);
+        assert_eq!(info.parts.len(), 2);
+        assert!(info.parts[1].solved);
+        assert_eq!(info.parts[1].answer, None);
+    }
+
+    #[test]
+    fn test_parse_unsolved() {
+        let page = PuzzlePage::parse(include_str!("../../tests/pages/2024_day_03.html")).unwrap();
+
+        assert_eq!(page.title, "Mull It Over");
+        assert_eq!(page.parts.len(), 1);
+        assert!(page.answers.is_empty());
+        assert_eq!(page.stars, Stars::None);
+    }
+
+    #[test]
+    fn test_parse_not_a_puzzle_page() {
+        let page = PuzzlePage::parse(include_str!("../../tests/pages/login.html"));
+        assert!(matches!(page, Err(PageError::NotAPuzzlePage)));
+    }
+}
@@ -3,20 +3,42 @@ use reqwest::{
     header::{COOKIE, USER_AGENT},
 };
 
+use super::{
+    cassette::{self, HttpMode},
+    user_config::get_contact,
+};
 use crate::error::AocError;
 
+static MISSING_CONTACT_WARNING: std::sync::Once = std::sync::Once::new();
+
 pub struct AocRequest {
     client: reqwest::Client,
+    user_agent: String,
 }
 
 impl AocRequest {
-    const AOC_USER_AGENT: &'static str =
-        "github.com/seblyng/cargo-aoc by sebastian@lyngjohansen.com and sivert-joh@hotmail.com";
+    const AOC_USER_AGENT: &'static str = "github.com/seblyng/cargo-aoc";
 
-    pub fn new() -> AocRequest {
-        AocRequest {
+    pub fn new() -> Result<AocRequest, AocError> {
+        Ok(AocRequest {
             client: reqwest::Client::new(),
-        }
+            user_agent: AocRequest::user_agent()?,
+        })
+    }
+
+    fn user_agent() -> Result<String, AocError> {
+        Ok(match get_contact()? {
+            Some(contact) => format!("{} by {}", AocRequest::AOC_USER_AGENT, contact),
+            None => {
+                MISSING_CONTACT_WARNING.call_once(|| {
+                    eprintln!(
+                        "warning: no contact set for requests to adventofcode.com. Set \
+                         AOC_CONTACT or `contact` in ~/.config/cargo-aoc/config.toml"
+                    );
+                });
+                AocRequest::AOC_USER_AGENT.to_owned()
+            }
+        })
     }
 
     fn get_token(&self) -> Result<String, dotenv::Error> {
@@ -24,12 +46,23 @@ impl AocRequest {
     }
 
     async fn request(self, req: reqwest::RequestBuilder) -> Result<Response, AocError> {
+        let mode = HttpMode::from_env()?;
+
+        // Replaying should work without a token
+        if let HttpMode::Replay(dir) = &mode {
+            return cassette::replay(dir, &req.build()?).await;
+        }
+
         let token = self.get_token()?.replace("session=", "");
-        Ok(req
+        let req = req
             .header(COOKIE, format!("session={}", token))
-            .header(USER_AGENT, AocRequest::AOC_USER_AGENT)
-            .send()
-            .await?)
+            .header(USER_AGENT, self.user_agent)
+            .build()?;
+
+        match mode {
+            HttpMode::Record(dir) => cassette::record(&dir, &self.client, req).await,
+            _ => Ok(self.client.execute(req).await?),
+        }
     }
 
     pub async fn get<U: IntoUrl>(self, url: U) -> Result<Response, AocError> {
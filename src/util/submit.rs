@@ -1,46 +1,43 @@
-use std::collections::HashMap;
-
 use clap::ArgMatches;
-use sanitize_html::rules::predefined::DEFAULT;
 
-use super::{Task, request::AocRequest};
-use crate::{error::AocError, task_config::Config};
+use super::{Task, get_part};
+use crate::{error::AocError, provider::get_provider};
 
-pub fn get_submit_task(matches: &ArgMatches) -> Option<Result<Task, AocError>> {
-    let task = matches.get_one::<String>("submit")?.parse::<u8>();
-    if let Err(e) = task {
-        return Some(Err(e.into()));
-    }
-    match task.unwrap() {
-        1 => Some(Ok(Task::One)),
-        2 => Some(Ok(Task::Two)),
-        _ => Some(Err(AocError::InvalidSubmitTask)),
-    }
+/// The response after submitting an answer
+#[derive(Debug)]
+pub struct Submission {
+    pub accepted: bool,
+    pub message: String,
 }
 
-fn parse_and_sanitize_output(output: &str) -> Option<String> {
-    let start = output.find("<article><p>")?;
-    let end = output.find("</p></article>")?;
-    let body = &output[start..end];
-    sanitize_html::sanitize_str(&DEFAULT, body).ok()
+pub fn get_submit_task(matches: &ArgMatches) -> Option<Result<Task, AocError>> {
+    matches.contains_id("submit").then(|| {
+        let part = get_part(matches);
+        let task = match matches.get_one::<String>("submit") {
+            Some(task) => match task.parse::<u8>()? {
+                1 => Task::One,
+                2 => Task::Two,
+                _ => return Err(AocError::InvalidSubmitTask),
+            },
+            // `--submit` without a value submits the part given with `--part`
+            None => part.ok_or(AocError::InvalidSubmitTask)?,
+        };
+
+        match part {
+            Some(part) if part != task => Err(AocError::SubmitOtherPart),
+            _ => Ok(task),
+        }
+    })
 }
 
 pub async fn submit(
-    output: &str,
+    answers: (Option<String>, Option<String>),
     task: Task,
     day: u32,
     year: i32,
-    config: Config,
-) -> Result<String, AocError> {
-    let (p1, p2) = config.get_answers(output);
+) -> Result<Submission, AocError> {
+    let (p1, p2) = answers;
     let answer = if task == Task::One { p1 } else { p2 }.ok_or(AocError::ParseStdout)?;
-    let url = format!("https://adventofcode.com/{}/day/{}/answer", year, day);
-
-    let mut form = HashMap::new();
-    form.insert("level", if task == Task::One { 1 } else { 2 }.to_string());
-    form.insert("answer", answer);
-    let res = AocRequest::new().post(&url, &form).await?;
 
-    let text = &res.text().await?;
-    parse_and_sanitize_output(text).ok_or(AocError::SanitizeHtml)
+    get_provider()?.submit(year, day, task, &answer).await
 }
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::file::{config_error, get_config_dir, read_toml};
+use crate::error::AocError;
+
+static USER_CONFIG_FILE: &str = "config.toml";
+
+/// Global settings for the user, stored in `~/.config/cargo-aoc/config.toml`
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UserConfig {
+    /// Contact information sent in the User-Agent of every request
+    pub contact: Option<String>,
+    /// Website hosting the puzzles, see [`crate::provider`]
+    pub provider: Option<String>,
+    /// Default for `--timeout`
+    pub timeout: Option<String>,
+    /// Default for `--max-memory`
+    pub max_memory: Option<String>,
+}
+
+impl UserConfig {
+    fn path() -> Option<PathBuf> {
+        get_config_dir().map(|dir| dir.join(USER_CONFIG_FILE))
+    }
+
+    /// The config, or the default one when there is none or it is invalid
+    /// outside of `--strict`
+    pub fn load() -> Result<Self, AocError> {
+        Self::read().or_else(|err| config_error(err).map(|_| Self::default()))
+    }
+
+    /// The config, failing when it is invalid so that it isn't overwritten
+    pub fn read() -> Result<Self, AocError> {
+        match Self::path() {
+            Some(path) if path.is_file() => read_toml(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), AocError> {
+        let path = Self::path().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "could not find home directory",
+            )
+        })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let s = toml::to_string(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, s)?;
+        Ok(())
+    }
+}
+
+/// The contact to use for the User-Agent. `AOC_CONTACT` takes precedence
+/// over the global config
+pub fn get_contact() -> Result<Option<String>, AocError> {
+    let contact = match dotenv::var("AOC_CONTACT") {
+        Ok(contact) => Some(contact),
+        Err(_) => UserConfig::load()?.contact,
+    };
+    Ok(contact
+        .map(|contact| contact.trim().to_owned())
+        .filter(|contact| !contact.is_empty()))
+}
@@ -0,0 +1,177 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::ArgMatches;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::{
+    assert::{assert_answer, assert_expected},
+    error::AocError,
+    provider::input_path,
+    report::Format,
+    run::{ensure_input, print_supervised, print_usage, start},
+    supervise::{get_limits, kill_group},
+    util::{
+        Task,
+        file::{
+            day_path, get_input_path, get_parse_config, get_root_path, get_running_args,
+            get_year_from_path,
+        },
+        get_day, get_lang, get_part,
+    },
+};
+
+/// Changes to these don't trigger a rerun. `.answers` is written by `--assert`
+const IGNORED: [&str; 5] = ["target", ".bench", ".git", "input", ".answers"];
+
+fn is_relevant(event: &Event, dir: &Path) -> bool {
+    let changed = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    );
+
+    changed
+        && event.paths.iter().any(|path| {
+            path.strip_prefix(dir).is_ok_and(|rel| {
+                !rel.components()
+                    .any(|c| IGNORED.iter().any(|ignored| c.as_os_str() == *ignored))
+            })
+        })
+}
+
+/// Editors usually trigger several events per save
+async fn debounce(rx: &mut UnboundedReceiver<()>) {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    while rx.try_recv().is_ok() {}
+}
+
+fn print_banner(ok: bool) {
+    if ok {
+        println!("\n\x1b[0;30;42m PASS \x1b[0m");
+    } else {
+        println!("\n\x1b[0;30;41m FAIL \x1b[0m");
+    }
+}
+
+struct Day {
+    day: u32,
+    year: i32,
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+/// Verifies the output with `--assert`, or against the example expectations
+/// in e.g `test.expected` next to the input if it exists
+async fn verify(
+    matches: &ArgMatches,
+    day: &Day,
+    input: &Path,
+    out: &str,
+) -> Result<Option<bool>, AocError> {
+    let config = get_parse_config(&day.root, &day.dir)?;
+    if matches.get_flag("assert") {
+        return assert_answer(out, day.day, day.year, config, get_part(matches))
+            .await
+            .map(Some);
+    }
+
+    let mut expected = input.as_os_str().to_owned();
+    expected.push(".expected");
+    let expected = PathBuf::from(expected);
+    if expected.exists() {
+        return assert_expected(out, &expected, config, get_part(matches)).map(Some);
+    }
+
+    Ok(None)
+}
+
+pub async fn watch(matches: &ArgMatches) -> Result<(), AocError> {
+    let root = get_root_path()?;
+    let year = get_year_from_path(&root)?;
+    let day = get_day(matches, year)?;
+    let day = Day {
+        day,
+        year,
+        dir: day_path(&root, day).await?,
+        root,
+    };
+    let input = get_input_path(matches, &day.dir)?;
+    if input == input_path(&day.dir, get_part(matches).map(Task::number)) {
+        ensure_input(day.day, day.year, &day.dir).await?;
+    }
+    let limits = get_limits(matches)?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let dir = day.dir.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res
+            && is_relevant(&event, &dir)
+        {
+            let _ = tx.send(());
+        }
+    })?;
+    watcher.watch(&day.dir, RecursiveMode::Recursive)?;
+
+    loop {
+        // Clear the screen and move the cursor to the top
+        print!("\x1b[2J\x1b[H");
+        println!("Watching {} for changes...\n", day.dir.display());
+
+        // Errors are shown like a failed run, and fixed by the next change
+        let args = get_running_args(matches, day.day, get_lang(matches)).await;
+        let child = match args.and_then(start) {
+            Ok(child) => child,
+            Err(err) => {
+                println!("{}", err);
+                print_banner(false);
+                if rx.recv().await.is_none() {
+                    return Ok(());
+                }
+                debounce(&mut rx).await;
+                continue;
+            }
+        };
+        let pid = child.pid();
+        let mut output =
+            tokio::task::spawn_blocking(move || print_supervised(child, limits, Format::Text));
+
+        let changed = tokio::select! {
+            out = &mut output => {
+                match out.map_err(std::io::Error::other)? {
+                    Ok((out, usage)) => {
+                        print_usage(&usage);
+                        match verify(matches, &day, &input, &out).await {
+                            Ok(Some(ok)) => print_banner(ok),
+                            Ok(None) => {}
+                            Err(err) => {
+                                println!("{}", err);
+                                print_banner(false);
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        println!("{}", err);
+                        print_banner(false);
+                    }
+                }
+                rx.recv().await
+            }
+            changed = rx.recv() => {
+                // Cancel the run that is in flight. It may have exited already
+                if let Some(pid) = pid {
+                    kill_group(pid);
+                }
+                let _ = output.await;
+                changed
+            }
+        };
+
+        if changed.is_none() {
+            return Ok(());
+        }
+        debounce(&mut rx).await;
+    }
+}
@@ -8,8 +8,15 @@ fn task_two(input: &[String]) -> usize {
 
 fn main() {
     let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+
+    // Set by `cargo aoc run --part` to only run one of the parts
+    let part = std::env::var("AOC_PART").ok();
+    if part.as_deref() != Some("2") {
+        time(Task::One, task_one, &input);
+    }
+    if part.as_deref() != Some("1") {
+        time(Task::Two, task_two, &input);
+    }
 }
 
 fn read_input<P>(path: P) -> Vec<String>